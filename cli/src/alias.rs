@@ -0,0 +1,144 @@
+use crate::flags::Alias;
+use crate::workflow::tokenize;
+
+/// Expand an alias invocation into command lines. `$1`..`$9` are replaced by
+/// positional arguments and a bare `$@` token by all of them. An alias without
+/// placeholders passes extra arguments on to its (single) command.
+pub fn expand(name: &str, alias: &Alias, args: &[String]) -> Result<Vec<Vec<String>>, String> {
+    let mut lines = Vec::new();
+    let mut max_index = 0;
+    let mut uses_all = false;
+
+    for (i, line) in alias.steps().iter().enumerate() {
+        let tokens =
+            tokenize(line).map_err(|e| format!("Alias '{}' step {}: {}", name, i + 1, e))?;
+        let mut expanded = Vec::new();
+        for token in tokens {
+            if token == "$@" {
+                uses_all = true;
+                expanded.extend(args.iter().cloned());
+                continue;
+            }
+            let mut out = String::new();
+            let mut chars = token.chars().peekable();
+            while let Some(c) = chars.next() {
+                match (c, chars.peek().and_then(|d| d.to_digit(10))) {
+                    ('$', Some(n)) if n > 0 => {
+                        chars.next();
+                        let n = n as usize;
+                        max_index = max_index.max(n);
+                        if let Some(arg) = args.get(n - 1) {
+                            out.push_str(arg);
+                        }
+                    }
+                    _ => out.push(c),
+                }
+            }
+            expanded.push(out);
+        }
+        lines.push(expanded);
+    }
+
+    if lines.is_empty() {
+        return Err(format!("Alias '{}' has no commands", name));
+    }
+    if args.len() < max_index {
+        return Err(format!(
+            "Alias '{}' expects {} argument{}, got {}",
+            name,
+            max_index,
+            if max_index == 1 { "" } else { "s" },
+            args.len()
+        ));
+    }
+    if !uses_all && args.len() > max_index {
+        if max_index == 0 && lines.len() == 1 {
+            lines[0].extend(args.iter().cloned());
+        } else {
+            return Err(format!(
+                "Alias '{}' takes {} argument{}, got {}",
+                name,
+                max_index,
+                if max_index == 1 { "" } else { "s" },
+                args.len()
+            ));
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn steps(lines: &[&str]) -> Alias {
+        Alias::Steps(strings(lines))
+    }
+
+    #[test]
+    fn test_expand_positional() {
+        let alias = steps(&[
+            "open $1.example.com",
+            "auth login $1",
+            "wait --load networkidle",
+        ]);
+        let lines = expand("login", &alias, &strings(&["staging"])).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                strings(&["open", "staging.example.com"]),
+                strings(&["auth", "login", "staging"]),
+                strings(&["wait", "--load", "networkidle"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_keeps_argument_spaces() {
+        let alias = Alias::Command("fill @search $1".to_string());
+        let lines = expand("search", &alias, &strings(&["red shoes"])).unwrap();
+        assert_eq!(lines, vec![strings(&["fill", "@search", "red shoes"])]);
+    }
+
+    #[test]
+    fn test_expand_all_args() {
+        let alias = Alias::Command("get text $@".to_string());
+        let lines = expand("t", &alias, &strings(&["@e1", "--save-as", "x"])).unwrap();
+        assert_eq!(
+            lines,
+            vec![strings(&["get", "text", "@e1", "--save-as", "x"])]
+        );
+    }
+
+    #[test]
+    fn test_expand_appends_args_without_placeholders() {
+        let alias = Alias::Command("snapshot -i".to_string());
+        let lines = expand("snap", &alias, &strings(&["-c"])).unwrap();
+        assert_eq!(lines, vec![strings(&["snapshot", "-i", "-c"])]);
+    }
+
+    #[test]
+    fn test_expand_argument_count_errors() {
+        let alias = steps(&["open $1", "fill @q $2"]);
+        let err = expand("go", &alias, &strings(&["a"])).unwrap_err();
+        assert!(err.contains("expects 2 arguments, got 1"), "{}", err);
+
+        let alias = steps(&["reload", "snapshot"]);
+        let err = expand("refresh", &alias, &strings(&["x"])).unwrap_err();
+        assert!(err.contains("takes 0 arguments"), "{}", err);
+    }
+
+    #[test]
+    fn test_expand_leaves_variables() {
+        let alias = Alias::Command("fill @e1 $${user} ${user}".to_string());
+        let lines = expand("f", &alias, &[]).unwrap();
+        assert_eq!(
+            lines,
+            vec![strings(&["fill", "@e1", "$${user}", "${user}"])]
+        );
+    }
+}
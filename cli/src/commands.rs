@@ -4,6 +4,7 @@ use std::io::{self, BufRead};
 
 use crate::color;
 use crate::flags::Flags;
+use crate::registry;
 use crate::validation::{is_valid_session_name, session_name_error};
 
 /// Error type for command parsing with contextual information
@@ -14,7 +15,7 @@ pub enum ParseError {
     /// Command exists but subcommand is invalid
     UnknownSubcommand {
         subcommand: String,
-        valid_options: &'static [&'static str],
+        valid_options: Vec<&'static str>,
     },
     /// Command/subcommand exists but required arguments are missing
     MissingArguments {
@@ -294,7 +295,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 _ => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: &["type", "inserttext"],
+                    valid_options: registry::subcommand_names("keyboard"),
                 }),
             }
         }
@@ -516,6 +517,10 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 i += 1;
             }
+            // The scan needs to know which snapshot text the page hides
+            if flags.injection_scan.is_some() {
+                obj.insert("hiddenText".to_string(), json!(true));
+            }
             Ok(cmd)
         }
 
@@ -668,7 +673,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 _ => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.unwrap_or("(none)").to_string(),
-                    valid_options: &["save", "login", "list", "delete", "show"],
+                    valid_options: registry::subcommand_names("auth"),
                 }),
             }
         }
@@ -741,6 +746,9 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
         // === Is (state checks) ===
         "is" => parse_is(&rest, &id),
 
+        // === Assertions ===
+        "assert" => parse_assert(&rest, &id),
+
         // === Find (locators) ===
         "find" => parse_find(&rest, &id),
 
@@ -820,10 +828,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                             "--sameSite" => {
                                 if let Some(same_site) = rest.get(i + 1) {
                                     // Validate sameSite value
-                                    if *same_site == "Strict"
-                                        || *same_site == "Lax"
-                                        || *same_site == "None"
-                                    {
+                                    if registry::SAME_SITE.contains(same_site) {
                                         cookie["sameSite"] = json!(same_site);
                                         i += 2;
                                     } else {
@@ -897,12 +902,11 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Window ===
         "window" => {
-            const VALID: &[&str] = &["new"];
             match rest.first().copied() {
                 Some("new") => Ok(json!({ "id": id, "action": "window_new" })),
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("window"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "window".to_string(),
@@ -926,7 +930,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Dialog ===
         "dialog" => {
-            const VALID: &[&str] = &["accept", "dismiss"];
             match rest.first().copied() {
                 Some("accept") => {
                     let mut cmd = json!({ "id": id, "action": "dialog", "response": "accept" });
@@ -935,9 +938,10 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                     }
                     Ok(cmd)
                 }
+                Some("dismiss") => Ok(json!({ "id": id, "action": "dialog", "response": "dismiss" })),
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("dialog"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "dialog".to_string(),
@@ -948,7 +952,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Debug ===
         "trace" => {
-            const VALID: &[&str] = &["start", "stop"];
             match rest.first().copied() {
                 Some("start") => Ok(json!({ "id": id, "action": "trace_start" })),
                 Some("stop") => {
@@ -960,7 +963,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("trace"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "trace".to_string(),
@@ -971,7 +974,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Profiler (CDP Tracing / Chromium profiling) ===
         "profiler" => {
-            const VALID: &[&str] = &["start", "stop"];
             match rest.first().copied() {
                 Some("start") => {
                     let mut cmd = json!({ "id": id, "action": "profiler_start" });
@@ -997,7 +999,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("profiler"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "profiler".to_string(),
@@ -1008,7 +1010,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Recording (Playwright native video recording) ===
         "record" => {
-            const VALID: &[&str] = &["start", "stop", "restart"];
             match rest.first().copied() {
                 Some("start") => {
                     let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1051,7 +1052,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("record"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "record".to_string(),
@@ -1067,6 +1068,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             let clear = rest.contains(&"--clear");
             Ok(json!({ "id": id, "action": "errors", "clear": clear }))
         }
+        "more" => Ok(json!({ "id": id, "action": "last_output" })),
         "highlight" => {
             let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "highlight".to_string(),
@@ -1077,7 +1079,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === State ===
         "state" => {
-            const VALID: &[&str] = &["save", "load", "list", "clear", "show", "clean", "rename"];
             match rest.first().copied() {
                 Some("save") => {
                     let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1180,7 +1181,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: VALID,
+                    valid_options: registry::subcommand_names("state"),
                 }),
                 None => Err(ParseError::MissingArguments {
                     context: "state".to_string(),
@@ -1203,8 +1204,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 context: "swipe".to_string(),
                 usage: "swipe <up|down|left|right> [distance]",
             })?;
-            let valid_directions = ["up", "down", "left", "right"];
-            if !valid_directions.contains(direction) {
+            if !registry::DIRECTIONS.contains(direction) {
                 return Err(ParseError::InvalidValue {
                     message: format!("Invalid swipe direction: {}", direction),
                     usage: "swipe <up|down|left|right> [distance]",
@@ -1228,13 +1228,51 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
-                    valid_options: &["list"],
+                    valid_options: registry::subcommand_names("device"),
                 }),
             }
         }
 
         "diff" => parse_diff(&rest, &id, flags),
 
+        // === Workflows (run locally by the CLI) ===
+        "run" => {
+            const USAGE: &str = "run <workflow.yaml|workflow.json> [--var <key=value>]...";
+            let mut file = None;
+            let mut vars = serde_json::Map::new();
+            let mut j = 0;
+            while j < rest.len() {
+                match rest[j] {
+                    "--var" => {
+                        let spec = rest.get(j + 1).ok_or_else(|| ParseError::MissingArguments {
+                            context: "run --var".to_string(),
+                            usage: USAGE,
+                        })?;
+                        let (key, value) =
+                            spec.split_once('=').ok_or_else(|| ParseError::InvalidValue {
+                                message: format!("--var expects key=value, got '{}'", spec),
+                                usage: USAGE,
+                            })?;
+                        vars.insert(key.to_string(), json!(value));
+                        j += 1;
+                    }
+                    other if file.is_none() && !other.starts_with("--") => file = Some(other),
+                    other => {
+                        return Err(ParseError::InvalidValue {
+                            message: format!("unexpected argument '{}' for run", other),
+                            usage: USAGE,
+                        })
+                    }
+                }
+                j += 1;
+            }
+            let file = file.ok_or_else(|| ParseError::MissingArguments {
+                context: "run".to_string(),
+                usage: USAGE,
+            })?;
+            Ok(json!({ "id": id, "action": "run", "file": file, "vars": vars }))
+        }
+
         _ => Err(ParseError::UnknownCommand {
             command: cmd.to_string(),
         }),
@@ -1242,8 +1280,6 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 }
 
 fn parse_diff(rest: &[&str], id: &str, flags: &Flags) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["snapshot", "screenshot", "url"];
-
     match rest.first().copied() {
         Some("snapshot") => {
             let mut cmd = json!({ "id": id, "action": "diff_snapshot" });
@@ -1502,7 +1538,7 @@ fn parse_diff(rest: &[&str], id: &str, flags: &Flags) -> Result<Value, ParseErro
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("diff"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "diff".to_string(),
@@ -1512,10 +1548,6 @@ fn parse_diff(rest: &[&str], id: &str, flags: &Flags) -> Result<Value, ParseErro
 }
 
 fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &[
-        "text", "html", "value", "attr", "url", "title", "count", "box", "styles",
-    ];
-
     match rest.first().copied() {
         Some("text") => {
             let sel = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1574,7 +1606,7 @@ fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("get"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "get".to_string(),
@@ -1584,8 +1616,6 @@ fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
 }
 
 fn parse_is(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["visible", "enabled", "checked"];
-
     match rest.first().copied() {
         Some("visible") => {
             let sel = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1610,7 +1640,7 @@ fn parse_is(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("is"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "is".to_string(),
@@ -1619,20 +1649,95 @@ fn parse_is(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     }
 }
 
-fn parse_find(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &[
-        "role",
-        "text",
-        "label",
-        "placeholder",
-        "alt",
-        "title",
-        "testid",
-        "first",
-        "last",
-        "nth",
-    ];
+fn parse_assert(rest: &[&str], id: &str) -> Result<Value, ParseError> {
+    let check = rest.first().copied().ok_or_else(|| ParseError::MissingArguments {
+        context: "assert".to_string(),
+        usage: "assert <text|url|title|count|visible|no-console-errors> [selector] [options]",
+    })?;
+    let usage = match check {
+        "text" => "assert text <selector> --contains|--equals|--matches <value> [--timeout <ms>]",
+        "url" => "assert url --contains|--equals|--matches <value> [--timeout <ms>]",
+        "title" => "assert title --contains|--equals|--matches <value> [--timeout <ms>]",
+        "count" => "assert count <selector> --eq|--gt|--lt <n> [--timeout <ms>]",
+        "visible" => "assert visible <selector> [--timeout <ms>]",
+        "no-console-errors" => "assert no-console-errors",
+        sub => {
+            return Err(ParseError::UnknownSubcommand {
+                subcommand: sub.to_string(),
+                valid_options: registry::subcommand_names("assert"),
+            })
+        }
+    };
+
+    let mut cmd = json!({ "id": id, "action": "assert", "check": check });
+    let mut j = 1;
+    if matches!(check, "text" | "count" | "visible") {
+        match rest.get(1) {
+            Some(sel) if !sel.starts_with("--") => cmd["selector"] = json!(sel),
+            _ => {
+                return Err(ParseError::MissingArguments {
+                    context: format!("assert {}", check),
+                    usage,
+                })
+            }
+        }
+        j = 2;
+    }
+
+    let text_check = matches!(check, "text" | "url" | "title");
+    let mut matchers = 0;
+    while j < rest.len() {
+        let flag = rest[j];
+        let value = rest.get(j + 1).copied();
+        match flag {
+            "--contains" | "--equals" | "--matches" if text_check => {
+                let value = value.ok_or_else(|| ParseError::MissingArguments {
+                    context: format!("assert {} {}", check, flag),
+                    usage,
+                })?;
+                cmd[&flag[2..]] = json!(value);
+                matchers += 1;
+            }
+            "--eq" | "--gt" | "--lt" if check == "count" => {
+                let n = value
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .ok_or_else(|| ParseError::InvalidValue {
+                        message: format!("{} expects a non-negative integer", flag),
+                        usage,
+                    })?;
+                cmd[&flag[2..]] = json!(n);
+                matchers += 1;
+            }
+            "--timeout" if check != "no-console-errors" => {
+                let ms = value
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .ok_or_else(|| ParseError::InvalidValue {
+                        message: "--timeout expects milliseconds".to_string(),
+                        usage,
+                    })?;
+                cmd["timeout"] = json!(ms);
+            }
+            other => {
+                return Err(ParseError::InvalidValue {
+                    message: format!("unexpected argument '{}' for assert {}", other, check),
+                    usage,
+                })
+            }
+        }
+        j += 2;
+    }
 
+    let needs_matcher = text_check || check == "count";
+    if needs_matcher && matchers != 1 {
+        return Err(ParseError::InvalidValue {
+            message: format!("assert {} takes exactly one matcher", check),
+            usage,
+        });
+    }
+    Ok(cmd)
+}
+
+fn parse_find(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     let locator = rest.first().ok_or_else(|| ParseError::MissingArguments {
         context: "find".to_string(),
         usage: "find <locator> <value> [action] [text]",
@@ -1751,14 +1856,12 @@ fn parse_find(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         _ => Err(ParseError::UnknownSubcommand {
             subcommand: locator.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("find"),
         }),
     }
 }
 
 fn parse_mouse(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["move", "down", "up", "wheel"];
-
     match rest.first().copied() {
         Some("move") => {
             let x_str = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1799,7 +1902,7 @@ fn parse_mouse(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("mouse"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "mouse".to_string(),
@@ -1809,18 +1912,6 @@ fn parse_mouse(rest: &[&str], id: &str) -> Result<Value, ParseError> {
 }
 
 fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &[
-        "viewport",
-        "device",
-        "geo",
-        "geolocation",
-        "offline",
-        "headers",
-        "credentials",
-        "auth",
-        "media",
-    ];
-
     match rest.first().copied() {
         Some("viewport") => {
             let w_str = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1882,6 +1973,98 @@ fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
                 .unwrap_or(true);
             Ok(json!({ "id": id, "action": "offline", "offline": off }))
         }
+        Some("network") => {
+            const PROFILES: &[&str] = registry::NETWORK_PROFILES;
+            const USAGE: &str = "set network <slow-3g|fast-3g|4g|off> | set network custom --download <kbps> --upload <kbps> --latency <ms>";
+            let mut profile = "custom";
+            let mut cmd = json!({ "id": id, "action": "network_conditions" });
+            let mut i = 1;
+            while i < rest.len() {
+                match rest[i] {
+                    flag @ ("--download" | "--upload" | "--latency") => {
+                        let n = rest
+                            .get(i + 1)
+                            .and_then(|v| v.parse::<f64>().ok())
+                            .filter(|n| *n >= 0.0)
+                            .ok_or_else(|| ParseError::InvalidValue {
+                                message: format!("{} requires a non-negative number", flag),
+                                usage: USAGE,
+                            })?;
+                        cmd[&flag[2..]] = json!(n);
+                        i += 1;
+                    }
+                    p if i == 1 && PROFILES.contains(&p) => profile = p,
+                    other => {
+                        return Err(ParseError::InvalidValue {
+                            message: format!(
+                                "Unknown network profile: {}. Valid profiles: {}",
+                                other,
+                                PROFILES.join(", ")
+                            ),
+                            usage: USAGE,
+                        });
+                    }
+                }
+                i += 1;
+            }
+            let has_custom_values = cmd.get("download").is_some()
+                || cmd.get("upload").is_some()
+                || cmd.get("latency").is_some();
+            if profile == "custom" && !has_custom_values {
+                return Err(ParseError::MissingArguments {
+                    context: "set network".to_string(),
+                    usage: USAGE,
+                });
+            }
+            cmd["profile"] = json!(profile);
+            Ok(cmd)
+        }
+        Some("cpu") => {
+            const USAGE: &str = "set cpu <slowdown-factor|off>";
+            let value = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                context: "set cpu".to_string(),
+                usage: USAGE,
+            })?;
+            let rate = if *value == "off" {
+                1.0
+            } else {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|r| *r >= 1.0)
+                    .ok_or_else(|| ParseError::InvalidValue {
+                        message: format!("Invalid CPU slowdown factor: {} (must be >= 1)", value),
+                        usage: USAGE,
+                    })?
+            };
+            Ok(json!({ "id": id, "action": "cpu_throttle", "rate": rate }))
+        }
+        Some("timeout") => {
+            const USAGE: &str = "set timeout <default|navigation|action|wait> <ms>";
+            let (Some(kind), Some(ms)) = (rest.get(1), rest.get(2)) else {
+                return Err(ParseError::MissingArguments {
+                    context: "set timeout".to_string(),
+                    usage: USAGE,
+                });
+            };
+            if !registry::TIMEOUT_KINDS.contains(kind) {
+                return Err(ParseError::InvalidValue {
+                    message: format!(
+                        "Unknown timeout kind: {}. Valid kinds: {}",
+                        kind,
+                        registry::TIMEOUT_KINDS.join(", ")
+                    ),
+                    usage: USAGE,
+                });
+            }
+            let timeout = ms.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                ParseError::InvalidValue {
+                    message: format!("Invalid timeout: {} (must be a positive number of ms)", ms),
+                    usage: USAGE,
+                }
+            })?;
+            Ok(json!({ "id": id, "action": "timeout", "kind": kind, "timeout": timeout }))
+        }
         Some("headers") => {
             let headers_json = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "set headers".to_string(),
@@ -1925,18 +2108,16 @@ fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("set"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "set".to_string(),
-            usage: "set <viewport|device|geo|offline|headers|credentials|media> [args...]",
+            usage: "set <viewport|device|geo|offline|network|cpu|headers|credentials|media> [args...]",
         }),
     }
 }
 
 fn parse_network(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["route", "unroute", "requests"];
-
     match rest.first().copied() {
         Some("route") => {
             let url = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -1965,20 +2146,65 @@ fn parse_network(rest: &[&str], id: &str) -> Result<Value, ParseError> {
             }
             Ok(cmd)
         }
+        Some("export") => {
+            const FORMATS: &[&str] = registry::EXPORT_FORMATS;
+            const USAGE: &str = "network export [<index>] [--filter <pattern>] [--as <curl|fetch|httpie|python-requests>] [--redact-auth]";
+            let mut cmd = json!({
+                "id": id,
+                "action": "requests_export",
+                "format": "curl",
+                "redactAuth": rest.contains(&"--redact-auth"),
+            });
+            let mut i = 1;
+            while i < rest.len() {
+                match rest[i] {
+                    "--filter" | "--as" => {
+                        let value = rest.get(i + 1).ok_or_else(|| ParseError::MissingArguments {
+                            context: format!("network export {}", rest[i]),
+                            usage: USAGE,
+                        })?;
+                        if rest[i] == "--as" {
+                            if !FORMATS.contains(value) {
+                                return Err(ParseError::InvalidValue {
+                                    message: format!(
+                                        "Unknown export format: {}. Valid formats: {}",
+                                        value,
+                                        FORMATS.join(", ")
+                                    ),
+                                    usage: USAGE,
+                                });
+                            }
+                            cmd["format"] = json!(value);
+                        } else {
+                            cmd["filter"] = json!(value);
+                        }
+                        i += 1;
+                    }
+                    "--redact-auth" => {}
+                    arg => {
+                        let index = arg.parse::<u64>().map_err(|_| ParseError::InvalidValue {
+                            message: format!("Invalid request index: {}", arg),
+                            usage: USAGE,
+                        })?;
+                        cmd["index"] = json!(index);
+                    }
+                }
+                i += 1;
+            }
+            Ok(cmd)
+        }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("network"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "network".to_string(),
-            usage: "network <route|unroute|requests> [args...]",
+            usage: "network <route|unroute|requests|export> [args...]",
         }),
     }
 }
 
 fn parse_storage(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["local", "session"];
-
     match rest.first().copied() {
         Some("local") | Some("session") => {
             let storage_type = rest.first().unwrap();
@@ -2014,7 +2240,7 @@ fn parse_storage(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
-            valid_options: VALID,
+            valid_options: registry::subcommand_names("storage"),
         }),
         None => Err(ParseError::MissingArguments {
             context: "storage".to_string(),
@@ -2058,18 +2284,46 @@ mod tests {
             cli_user_agent: false,
             cli_proxy: false,
             cli_proxy_bypass: false,
+            cli_proxy_pac: false,
             cli_allow_file_access: false,
             cli_annotate: false,
             cli_download_path: false,
+            cli_ca_certs: false,
+            cli_resolve: false,
+            cli_client_certs: false,
+            cli_default_timeout: false,
             annotate: false,
             color_scheme: None,
             download_path: None,
             content_boundaries: false,
+            injection_scan: None,
             max_output: None,
+            max_tokens: None,
+            offset: None,
+            page: None,
+            default_timeout: None,
+            navigation_timeout: None,
+            action_timeout: None,
+            wait_timeout: None,
             allowed_domains: None,
+            block: None,
+            proxy_pac: None,
+            proxy_password_env: None,
+            proxy_list: None,
+            proxy_rotate: false,
+            ca_certs: Vec::new(),
+            resolve: Vec::new(),
+            client_certs: Vec::new(),
+            client_cert_error: None,
             action_policy: None,
             confirm_actions: None,
             confirm_interactive: false,
+            report: None,
+            aliases: Default::default(),
+            dry_run: false,
+            field: None,
+            format: None,
+            out: None,
 
         }
     }
@@ -2494,6 +2748,21 @@ mod tests {
 
     // === Tabs ===
 
+    #[test]
+    fn test_dialog_dismiss() {
+        let cmd = parse_command(&args("dialog dismiss"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "dialog");
+        assert_eq!(cmd["response"], "dismiss");
+    }
+
+    #[test]
+    fn test_dialog_unknown_subcommand() {
+        let err = parse_command(&args("dialog close"), &default_flags()).unwrap_err();
+        assert!(matches!(err, ParseError::UnknownSubcommand { .. }));
+        assert!(err.format().contains("accept"));
+        assert!(err.format().contains("dismiss"));
+    }
+
     #[test]
     fn test_tab_new() {
         let cmd = parse_command(&args("tab new"), &default_flags()).unwrap();
@@ -2602,6 +2871,15 @@ mod tests {
     fn test_snapshot() {
         let cmd = parse_command(&args("snapshot"), &default_flags()).unwrap();
         assert_eq!(cmd["action"], "snapshot");
+        assert!(cmd.get("hiddenText").is_none());
+    }
+
+    #[test]
+    fn test_snapshot_requests_hidden_text_for_injection_scan() {
+        let mut flags = default_flags();
+        flags.injection_scan = Some("warn".to_string());
+        let cmd = parse_command(&args("snapshot"), &flags).unwrap();
+        assert_eq!(cmd["hiddenText"], true);
     }
 
     #[test]
@@ -3025,6 +3303,139 @@ mod tests {
         assert!(cmd.get("value").is_none());
     }
 
+    // === Network/CPU Throttling Tests ===
+
+    #[test]
+    fn test_set_network_profile() {
+        let cmd = parse_command(&args("set network slow-3g"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "network_conditions");
+        assert_eq!(cmd["profile"], "slow-3g");
+    }
+
+    #[test]
+    fn test_set_network_off() {
+        let cmd = parse_command(&args("set network off"), &default_flags()).unwrap();
+        assert_eq!(cmd["profile"], "off");
+    }
+
+    #[test]
+    fn test_set_network_custom() {
+        let cmd = parse_command(
+            &args("set network custom --download 1000 --upload 500 --latency 80"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["profile"], "custom");
+        assert_eq!(cmd["download"], 1000.0);
+        assert_eq!(cmd["upload"], 500.0);
+        assert_eq!(cmd["latency"], 80.0);
+    }
+
+    #[test]
+    fn test_set_network_custom_without_profile_name() {
+        let cmd = parse_command(&args("set network --latency 300"), &default_flags()).unwrap();
+        assert_eq!(cmd["profile"], "custom");
+        assert_eq!(cmd["latency"], 300.0);
+    }
+
+    #[test]
+    fn test_set_network_custom_requires_values() {
+        let result = parse_command(&args("set network custom"), &default_flags());
+        assert!(matches!(result, Err(ParseError::MissingArguments { .. })));
+    }
+
+    #[test]
+    fn test_set_network_unknown_profile() {
+        let result = parse_command(&args("set network 5g"), &default_flags());
+        let err = result.unwrap_err();
+        assert!(err.format().contains("Unknown network profile: 5g"));
+    }
+
+    #[test]
+    fn test_set_cpu() {
+        let cmd = parse_command(&args("set cpu 4"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "cpu_throttle");
+        assert_eq!(cmd["rate"], 4.0);
+    }
+
+    #[test]
+    fn test_set_cpu_off() {
+        let cmd = parse_command(&args("set cpu off"), &default_flags()).unwrap();
+        assert_eq!(cmd["rate"], 1.0);
+    }
+
+    #[test]
+    fn test_set_cpu_invalid() {
+        let result = parse_command(&args("set cpu 0.5"), &default_flags());
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_set_timeout() {
+        let cmd = parse_command(&args("set timeout navigation 60000"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "timeout");
+        assert_eq!(cmd["kind"], "navigation");
+        assert_eq!(cmd["timeout"], 60000);
+    }
+
+    #[test]
+    fn test_set_timeout_invalid() {
+        let result = parse_command(&args("set timeout page 1000"), &default_flags());
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+        let result = parse_command(&args("set timeout wait 0"), &default_flags());
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+        let result = parse_command(&args("set timeout wait"), &default_flags());
+        assert!(matches!(result, Err(ParseError::MissingArguments { .. })));
+    }
+
+    // === Network Export Tests ===
+
+    #[test]
+    fn test_network_export_defaults() {
+        let cmd = parse_command(&args("network export"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "requests_export");
+        assert_eq!(cmd["format"], "curl");
+        assert_eq!(cmd["redactAuth"], false);
+        assert!(cmd.get("index").is_none());
+    }
+
+    #[test]
+    fn test_network_export_index_and_format() {
+        let cmd = parse_command(
+            &args("network export 3 --as python-requests --redact-auth"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["index"], 3);
+        assert_eq!(cmd["format"], "python-requests");
+        assert_eq!(cmd["redactAuth"], true);
+    }
+
+    #[test]
+    fn test_network_export_filter() {
+        let cmd = parse_command(
+            &args("network export --filter /api/ --as fetch"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["filter"], "/api/");
+        assert_eq!(cmd["format"], "fetch");
+    }
+
+    #[test]
+    fn test_network_export_invalid_format() {
+        let result = parse_command(&args("network export --as wget"), &default_flags());
+        let err = result.unwrap_err();
+        assert!(matches!(err, ParseError::InvalidValue { .. }));
+        assert!(err.format().contains("Unknown export format: wget"));
+    }
+
+    #[test]
+    fn test_network_export_invalid_index() {
+        let result = parse_command(&args("network export first"), &default_flags());
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+    }
+
     // === Download Tests ===
 
     #[test]
@@ -3642,6 +4053,116 @@ mod tests {
         ));
     }
 
+    // === Assert Tests ===
+
+    #[test]
+    fn test_assert_text_contains() {
+        let cmd = parse_command(
+            &["assert", "text", "@e1", "--contains", "Welcome back"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "assert");
+        assert_eq!(cmd["check"], "text");
+        assert_eq!(cmd["selector"], "@e1");
+        assert_eq!(cmd["contains"], "Welcome back");
+    }
+
+    #[test]
+    fn test_assert_url_matches_with_timeout() {
+        let cmd = parse_command(
+            &args("assert url --matches /dashboard$ --timeout 10000"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["check"], "url");
+        assert_eq!(cmd["matches"], "/dashboard$");
+        assert_eq!(cmd["timeout"], 10000);
+        assert!(cmd.get("selector").is_none());
+    }
+
+    #[test]
+    fn test_assert_count() {
+        let cmd = parse_command(&args("assert count .row --eq 3"), &default_flags()).unwrap();
+        assert_eq!(cmd["selector"], ".row");
+        assert_eq!(cmd["eq"], 3);
+    }
+
+    #[test]
+    fn test_assert_count_rejects_negative() {
+        let err = parse_command(&args("assert count .row --eq -1"), &default_flags()).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidValue { .. }));
+        assert!(err.format().contains("--eq expects a non-negative integer"));
+    }
+
+    #[test]
+    fn test_assert_visible_and_console() {
+        let cmd = parse_command(&args("assert visible @e2"), &default_flags()).unwrap();
+        assert_eq!(cmd["check"], "visible");
+        let cmd = parse_command(&args("assert no-console-errors"), &default_flags()).unwrap();
+        assert_eq!(cmd["check"], "no-console-errors");
+    }
+
+    #[test]
+    fn test_assert_errors() {
+        assert!(matches!(
+            parse_command(&args("assert text --contains x"), &default_flags()),
+            Err(ParseError::MissingArguments { .. })
+        ));
+        assert!(matches!(
+            parse_command(&args("assert title"), &default_flags()),
+            Err(ParseError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_command(&args("assert count .row --eq x"), &default_flags()),
+            Err(ParseError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_command(&args("assert url --contains a --equals b"), &default_flags()),
+            Err(ParseError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_command(&args("assert enabled @e1"), &default_flags()),
+            Err(ParseError::UnknownSubcommand { .. })
+        ));
+    }
+
+    // === Workflow Tests ===
+
+    #[test]
+    fn test_run_workflow() {
+        let cmd = parse_command(&args("run flow.yaml"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "run");
+        assert_eq!(cmd["file"], "flow.yaml");
+        assert_eq!(cmd["vars"], json!({}));
+    }
+
+    #[test]
+    fn test_run_workflow_vars() {
+        let cmd = parse_command(
+            &args("run flow.json --var user=alice --var url=https://a.test/?x=1"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["vars"]["user"], "alice");
+        assert_eq!(cmd["vars"]["url"], "https://a.test/?x=1");
+    }
+
+    #[test]
+    fn test_run_workflow_errors() {
+        assert!(matches!(
+            parse_command(&args("run"), &default_flags()),
+            Err(ParseError::MissingArguments { .. })
+        ));
+        assert!(matches!(
+            parse_command(&args("run flow.yaml --var nope"), &default_flags()),
+            Err(ParseError::InvalidValue { .. })
+        ));
+    }
+
     // === Scroll Tests ===
 
     #[test]
@@ -0,0 +1,366 @@
+use crate::color;
+use crate::exit_code;
+use crate::connection::{get_socket_dir, list_sessions};
+use crate::flags::load_config;
+use crate::plugin;
+use crate::registry::{self, Command, Complete, Opt};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+const BASH: &str = r#"# agent-browser bash completion
+# Add to ~/.bashrc: eval "$(agent-browser completions bash)"
+_agent_browser() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words
+    read -ra words <<< "$line"
+    [[ "$line" =~ [[:space:]]$ ]] && words+=("")
+    local cur="${words[${#words[@]}-1]}"
+    # Bash splits words at COMP_WORDBREAKS (@, :, =); only replace its last piece
+    local prefix="${cur%"${COMP_WORDS[COMP_CWORD]}"}"
+    local IFS=$'\n'
+    local -a candidates
+    candidates=($(agent-browser __complete "${words[@]:1}" 2>/dev/null | cut -f1))
+    COMPREPLY=($(compgen -W "${candidates[*]}" -- "$cur"))
+    COMPREPLY=("${COMPREPLY[@]#"$prefix"}")
+}
+complete -o default -F _agent_browser agent-browser
+"#;
+
+const ZSH: &str = r#"#compdef agent-browser
+# agent-browser zsh completion
+# Add to ~/.zshrc: eval "$(agent-browser completions zsh)"
+_agent_browser() {
+    local -a candidates
+    local line value
+    for line in "${(@f)$(agent-browser __complete "${(@)words[2,CURRENT]}" 2>/dev/null)}"; do
+        [[ -z $line ]] && continue
+        value="${line%%$'\t'*}"
+        if [[ $line == *$'\t'* ]]; then
+            candidates+=("${value//:/\\:}:${line#*$'\t'}")
+        else
+            candidates+=("${value//:/\\:}")
+        fi
+    done
+    if (( ${#candidates} )); then
+        _describe 'agent-browser' candidates
+    else
+        _files
+    fi
+}
+if [[ "$funcstack[1]" == "_agent_browser" ]]; then
+    _agent_browser "$@"
+else
+    compdef _agent_browser agent-browser
+fi
+"#;
+
+const FISH: &str = r#"# agent-browser fish completion
+# Save as ~/.config/fish/completions/agent-browser.fish, or:
+#   agent-browser completions fish | source
+function __agent_browser_complete
+    set -l tokens (commandline -opc)
+    set -l cur (commandline -ct)
+    set -l out (agent-browser __complete $tokens[2..-1] "$cur" 2>/dev/null)
+    if test (count $out) -eq 0
+        __fish_complete_path "$cur"
+    else
+        printf '%s\n' $out
+    end
+end
+complete -c agent-browser -f -a '(__agent_browser_complete)'
+"#;
+
+/// File stems (or full names) of `*.json` files in a directory, sorted.
+fn json_files(dir: &Path, with_extension: bool) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let stem = name.strip_suffix(".json")?;
+            Some(if with_extension {
+                name.clone()
+            } else {
+                stem.to_string()
+            })
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn agent_browser_dir(sub: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".agent-browser").join(sub))
+}
+
+fn refs_path(session: &str) -> PathBuf {
+    get_socket_dir().join(format!("{}.refs.json", session))
+}
+
+/// Remember the refs from a snapshot response so `@eN` can be completed
+/// without asking the daemon. Best effort: failures are ignored.
+pub fn save_refs(session: &str, data: &Value) {
+    let Some(refs) = data.get("refs").and_then(|r| r.as_object()) else {
+        return;
+    };
+    let entries: Vec<Value> = refs
+        .iter()
+        .map(|(id, info)| {
+            let role = info.get("role").and_then(|v| v.as_str()).unwrap_or("");
+            let name = info.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            json!([format!("@{}", id), format!("{} {}", role, name).trim()])
+        })
+        .collect();
+    let _ = fs::write(refs_path(session), Value::Array(entries).to_string());
+}
+
+fn load_refs(session: &str) -> Vec<(String, String)> {
+    let entries: Vec<(String, String)> = fs::read_to_string(refs_path(session))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    entries
+}
+
+fn dynamic(source: Complete, session: &str) -> Vec<(String, String)> {
+    let plain = |names: Vec<String>| names.into_iter().map(|n| (n, String::new())).collect();
+    match source {
+        Complete::Files => Vec::new(),
+        Complete::Refs => load_refs(session),
+        Complete::Sessions => plain(list_sessions()),
+        Complete::AuthProfiles => agent_browser_dir("auth")
+            .map(|d| plain(json_files(&d, false)))
+            .unwrap_or_default(),
+        Complete::States => agent_browser_dir("sessions")
+            .map(|d| plain(json_files(&d, false)))
+            .unwrap_or_default(),
+        Complete::StateFiles => agent_browser_dir("sessions")
+            .map(|d| plain(json_files(&d, true)))
+            .unwrap_or_default(),
+    }
+}
+
+fn values(values: &[&str], source: Complete, session: &str) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = values
+        .iter()
+        .map(|v| (v.to_string(), String::new()))
+        .collect();
+    out.extend(dynamic(source, session));
+    out
+}
+
+fn options(opts: &[Opt]) -> impl Iterator<Item = (String, String)> + '_ {
+    opts.iter()
+        .map(|o| (o.name.to_string(), o.description.to_string()))
+}
+
+fn top_level() -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = registry::COMMANDS
+        .iter()
+        .map(|c| (c.name.to_string(), c.summary.to_string()))
+        .collect();
+    if let Some(aliases) = load_config(&[]).ok().and_then(|c| c.aliases) {
+        out.extend(aliases.into_keys().map(|a| (a, "Alias".to_string())));
+    }
+    out.extend(
+        plugin::discover()
+            .into_iter()
+            .map(|(name, _)| (name, "Plugin".to_string())),
+    );
+    out
+}
+
+/// Completion candidates for the last of `words` (the command line after the
+/// program name, cursor word last) as `(value, description)` pairs. An empty
+/// result lets the shell fall back to file names.
+pub fn complete(words: &[String]) -> Vec<(String, String)> {
+    let (cur, before) = match words.split_last() {
+        Some((cur, before)) => (cur.as_str(), before),
+        None => ("", words),
+    };
+    let mut session = env::var("AGENT_BROWSER_SESSION").unwrap_or_else(|_| "default".to_string());
+    let mut command: Option<&'static Command> = None;
+    let mut positional = 0;
+    let mut pending: Option<&'static Opt> = None;
+
+    for word in before {
+        if let Some(opt) = pending.take() {
+            if opt.name == "--session" {
+                session = word.clone();
+            }
+            continue;
+        }
+        if word.starts_with('-') && word.len() > 1 {
+            let opt = command
+                .and_then(|c| c.find_option(word))
+                .or_else(|| registry::global_option(word));
+            pending = opt.filter(|o| o.value.is_some());
+            continue;
+        }
+        match command {
+            None => match registry::find(word) {
+                Some(c) => command = Some(c),
+                // Aliases and plugins take arguments we know nothing about
+                None => return Vec::new(),
+            },
+            Some(c) => {
+                let sub = c.subcommands.iter().find(|s| s.matches(word));
+                match sub {
+                    Some(sub) if positional == 0 => command = Some(sub),
+                    _ => positional += 1,
+                }
+            }
+        }
+    }
+
+    let mut candidates = if let Some(opt) = pending {
+        values(opt.values, opt.complete, &session)
+    } else if cur.starts_with('-') {
+        let mut out: Vec<(String, String)> = command
+            .map(|c| options(c.options).collect())
+            .unwrap_or_default();
+        out.extend(options(registry::GLOBAL_OPTIONS));
+        out
+    } else {
+        match command {
+            None => top_level(),
+            Some(c) => {
+                let mut out = Vec::new();
+                if positional == 0 {
+                    out.extend(
+                        c.subcommands
+                            .iter()
+                            .map(|s| (s.name.to_string(), s.summary.to_string())),
+                    );
+                }
+                let arg = c
+                    .args
+                    .get(positional)
+                    .or_else(|| c.args.last().filter(|a| a.variadic));
+                if let Some(arg) = arg {
+                    out.extend(values(arg.values, arg.complete, &session));
+                }
+                out
+            }
+        }
+    };
+    candidates.retain(|(value, _)| value.starts_with(cur));
+    candidates
+}
+
+/// Handle the hidden `__complete <words...>` command used by the scripts.
+pub fn run_complete(words: &[String]) -> ! {
+    for (value, description) in complete(words) {
+        if description.is_empty() {
+            println!("{}", value);
+        } else {
+            println!("{}\t{}", value, description);
+        }
+    }
+    exit(0);
+}
+
+/// Handle `completions <bash|zsh|fish>` without the daemon.
+pub fn run_completions(args: &[String], json_mode: bool) -> ! {
+    let script = match args.get(1).map(String::as_str) {
+        Some("bash") => BASH,
+        Some("zsh") => ZSH,
+        Some("fish") => FISH,
+        other => {
+            let msg = match other {
+                Some(shell) => format!(
+                    "Unknown shell: {}. Valid options: {}",
+                    shell,
+                    registry::SHELLS.join(", ")
+                ),
+                None => "Missing arguments for: completions\nUsage: agent-browser completions <bash|zsh|fish>".to_string(),
+            };
+            if json_mode {
+                println!("{}", json!({ "success": false, "error": msg }));
+            } else {
+                eprintln!("{} {}", color::error_indicator(), msg);
+            }
+            exit(exit_code::USAGE);
+        }
+    };
+    print!("{}", script);
+    exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values_for(line: &str) -> Vec<String> {
+        let mut words: Vec<String> = line.split_whitespace().map(String::from).collect();
+        if line.ends_with(' ') {
+            words.push(String::new());
+        }
+        complete(&words).into_iter().map(|(v, _)| v).collect()
+    }
+
+    #[test]
+    fn test_complete_commands() {
+        let found = values_for("sn");
+        assert!(found.contains(&"snapshot".to_string()));
+        assert!(!found.contains(&"open".to_string()));
+    }
+
+    #[test]
+    fn test_complete_subcommands() {
+        assert_eq!(
+            values_for("get ")[..3],
+            ["text".to_string(), "html".to_string(), "value".to_string()]
+        );
+        assert_eq!(values_for("set net"), vec!["network"]);
+        assert_eq!(
+            values_for("--session work set network "),
+            vec!["slow-3g", "fast-3g", "4g", "custom", "off"]
+        );
+    }
+
+    #[test]
+    fn test_complete_enums() {
+        assert_eq!(values_for("swipe "), vec!["up", "down", "left", "right"]);
+        assert_eq!(
+            values_for("set media light "),
+            vec!["dark", "light", "reduced-motion"]
+        );
+        assert_eq!(
+            values_for("wait --load "),
+            vec!["load", "domcontentloaded", "networkidle"]
+        );
+        assert_eq!(values_for("--color-scheme d"), vec!["dark"]);
+    }
+
+    #[test]
+    fn test_complete_flags() {
+        let found = values_for("snapshot --");
+        assert!(found.contains(&"--interactive".to_string()));
+        assert!(found.contains(&"--json".to_string()));
+        assert!(!values_for("open --").contains(&"--interactive".to_string()));
+    }
+
+    #[test]
+    fn test_complete_skips_flag_values() {
+        // "get" is the value of --session, not the command
+        let found = values_for("--session get ");
+        assert!(found.contains(&"open".to_string()));
+        // -s takes a value for scroll, so "down" is not the direction
+        assert_eq!(
+            values_for("scroll -s main "),
+            vec!["up", "down", "left", "right"]
+        );
+    }
+
+    #[test]
+    fn test_complete_files_fallback() {
+        assert!(values_for("screenshot @e1 ").is_empty());
+        assert!(values_for("my-alias-or-plugin ").is_empty());
+    }
+}
@@ -0,0 +1,737 @@
+use crate::color;
+use crate::exit_code;
+use crate::flags::{
+    config_sources, load_config, read_config_file, resolve_config_value, selected_profile,
+    strip_json_comments, user_config_path, Config, PROJECT_CONFIG_FILENAME,
+};
+use crate::registry::{self, Opt};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+const TEMPLATE: &str = r#"// agent-browser configuration.
+//
+// ~/.agent-browser/config.json applies everywhere; ./agent-browser.json
+// overrides it for one project. AGENT_BROWSER_* environment variables and
+// command-line flags override both. Uncomment the settings you need, then
+// check the file with: agent-browser config validate <file>
+//
+// String values can use ${VAR} or ${VAR:-default} for environment variables,
+// and "$file:./path" to load a value from a file (JSON files are parsed).
+{
+  // "extends": "./base.json",            // Inherit settings from another file
+
+  // Browser
+  // "headed": true,                      // Show the browser window
+  // "executablePath": "/usr/bin/chromium",
+  // "extensions": ["./my-extension"],
+  // "args": "--no-sandbox",              // Extra browser launch args, comma-separated
+  // "userAgent": "my-agent/1.0",
+  // "colorScheme": "dark",               // dark, light or no-preference
+  // "downloadPath": "./downloads",
+
+  // Sessions and state
+  // "session": "default",
+  // "sessionName": "my-app",             // Auto-save/restore state under this name
+  // "profile": "~/.agent-browser/profiles/main",
+  // "state": "./auth-state.json",
+
+  // Network
+  // "proxy": "http://proxy.example.com:8080",
+  // "proxyBypass": "localhost,*.internal",
+  // "proxyPasswordEnv": "PROXY_PASSWORD", // Read the proxy password from this env var
+  // "ignoreHttpsErrors": false,
+  // "caCerts": ["./corp-ca.pem"],
+  // "resolve": ["app.test:443:127.0.0.1"],
+
+  // Safety
+  // "allowedDomains": ["example.com", "*.example.com"],
+  // "actionPolicy": "./policy.json",
+  // "confirmActions": "eval,download",
+  // "contentBoundaries": true,           // Wrap page output in boundary markers
+  // "injectionScan": "warn",             // Flag likely prompt injection (warn, strip)
+  // "maxOutput": 50000,                  // Truncate page output to this many characters
+
+  // Output
+  // "json": false,
+  // "full": false,
+  // "annotate": false,
+
+  // Profiles: "agent-browser --env staging ..." layers one over the rest
+  // "profiles": {
+  //   "staging": { "proxy": "http://staging-proxy:8080", "allowedDomains": ["staging.example.com"] },
+  //   "ci": { "headed": false, "actionPolicy": "./ci-policy.json" }
+  // },
+
+  // Aliases: "agent-browser login" runs these steps
+  // "aliases": {
+  //   "login": ["open https://example.com/login", "fill #user $1", "click #submit"]
+  // }
+}
+"#;
+
+fn fail(msg: &str, json_mode: bool) -> ! {
+    if json_mode {
+        println!("{}", json!({ "success": false, "error": msg }));
+    } else {
+        eprintln!("{} {}", color::error_indicator(), msg);
+    }
+    exit(exit_code::USAGE);
+}
+
+/// `maxOutput` -> `max-output`.
+fn kebab(key: &str) -> String {
+    let mut out = String::new();
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The global flag for a config key: `maxOutput` -> `--max-output`. List
+/// keys use the singular, repeatable flag (`extensions` -> `--extension`).
+fn flag_for(key: &str) -> Option<&'static Opt> {
+    let name = format!("--{}", kebab(key));
+    registry::global_option(&name)
+        .or_else(|| name.strip_suffix('s').and_then(registry::global_option))
+}
+
+/// The environment variable for a config key, mirroring `parse_flags`.
+fn env_for(key: &str) -> Option<String> {
+    match key {
+        "headers" | "cdp" | "aliases" | "profiles" => None,
+        "device" => Some("AGENT_BROWSER_IOS_DEVICE".to_string()),
+        _ => Some(format!(
+            "AGENT_BROWSER_{}",
+            kebab(key).replace('-', "_").to_uppercase()
+        )),
+    }
+}
+
+/// The value a global flag was given on the command line, if any.
+fn flag_value(args: &[String], opt: &Opt) -> Option<Value> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == opt.name || opt.short == Some(arg) {
+            if opt.value.is_some() {
+                if let Some(v) = args.get(i + 1) {
+                    found.push(json!(v));
+                    i += 1;
+                }
+            } else {
+                // Same as parse_bool_arg: a bare flag is true
+                match args.get(i + 1).map(String::as_str) {
+                    Some(v @ ("true" | "false")) => {
+                        found.push(json!(v == "true"));
+                        i += 1;
+                    }
+                    _ => found.push(json!(true)),
+                }
+            }
+        } else if registry::global_option(arg).is_some_and(|o| o.value.is_some()) {
+            i += 1;
+        }
+        i += 1;
+    }
+    if opt.repeatable {
+        (!found.is_empty()).then_some(Value::Array(found))
+    } else {
+        found.pop()
+    }
+}
+
+/// The environment override for a key, as `parse_flags` applies it: boolean
+/// variables only count when truthy, others when non-empty.
+fn env_value(key: &str, opt: Option<&Opt>) -> Option<(String, Value)> {
+    let name = env_for(key)?;
+    let value = env::var(&name).ok().filter(|v| !v.is_empty())?;
+    if opt.is_some_and(|o| o.value.is_none()) {
+        let truthy = !matches!(value.to_lowercase().as_str(), "0" | "false" | "no");
+        return truthy.then_some((name, json!(true)));
+    }
+    Some((name, json!(value)))
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&strip_json_comments(&content)).ok()
+}
+
+/// Push a config file's raw JSON, preceded by the files it `extends`.
+fn file_layers(path: &Path, label: String, out: &mut Vec<(String, Value)>, depth: usize) {
+    let Some(value) = read_json(path) else {
+        return;
+    };
+    // Cycles are an error when loading; just stop following them here
+    if let Some(base) = value["extends"].as_str().filter(|_| depth < 8) {
+        let base_path = path.parent().unwrap_or(Path::new(".")).join(base);
+        let base_label = format!("{} via {}", base_path.display(), label);
+        file_layers(&base_path, base_label, out, depth + 1);
+    }
+    out.push((label, value));
+}
+
+/// One setting's effective value and where it came from: a flag, an
+/// environment variable, a config file or one of its profiles, or `default`
+/// when unset.
+struct Setting {
+    key: String,
+    value: Value,
+    source: String,
+}
+
+fn settings(args: &[String]) -> Result<Vec<Setting>, String> {
+    let sources = config_sources(args)?;
+    let profile = selected_profile(args)?;
+    let config = serde_json::to_value(load_config(args)?).unwrap_or_default();
+
+    // The last file to set a key is the one whose value won; the selected
+    // profile is layered over all of the files
+    let mut files: Vec<(String, Value)> = Vec::new();
+    for source in &sources {
+        let label = format!("{} {}", source.origin, source.path.display());
+        file_layers(&source.path, label, &mut files, 0);
+    }
+    let mut file_source: BTreeMap<String, String> = BTreeMap::new();
+    for (label, value) in &files {
+        if let Some(map) = value.as_object() {
+            for key in map.keys() {
+                file_source.insert(key.clone(), label.clone());
+            }
+        }
+    }
+    if let Some(name) = &profile {
+        for (label, value) in &files {
+            if let Some(map) = value["profiles"][name].as_object() {
+                for key in map.keys() {
+                    file_source.insert(key.clone(), format!("profile {} in {}", name, label));
+                }
+            }
+        }
+    }
+
+    Ok(Config::keys()
+        .into_iter()
+        .filter(|key| key != "profiles")
+        .map(|key| {
+            let opt = flag_for(&key);
+            let (value, source) = if let Some(v) = opt.and_then(|o| flag_value(args, o)) {
+                (v, opt.map(|o| o.name.to_string()).unwrap_or_default())
+            } else if let Some((name, v)) = env_value(&key, opt) {
+                (v, name)
+            } else {
+                match config.get(&key) {
+                    Some(v) if !v.is_null() => (
+                        v.clone(),
+                        file_source
+                            .get(&key)
+                            .cloned()
+                            .unwrap_or_else(|| "config".to_string()),
+                    ),
+                    _ => (Value::Null, "default".to_string()),
+                }
+            };
+            Setting { key, value, source }
+        })
+        .collect())
+}
+
+fn unknown_key(key: &str, keys: &[String]) -> String {
+    let normalize = |k: &str| k.replace(['-', '_'], "").to_lowercase();
+    let last = key.rsplit('.').next().unwrap_or(key);
+    match keys.iter().find(|k| normalize(k) == normalize(last)) {
+        Some(k) => format!("unknown key '{}' (did you mean '{}'?)", key, k),
+        None => format!("unknown key '{}'", key),
+    }
+}
+
+/// Check a config file strictly: the JSON must parse, `${VAR}`, `$file:` and
+/// `extends` references must resolve, every key must be known, and every
+/// value must have the right type.
+fn validate(content: &str, path: &Path) -> Vec<String> {
+    let mut value: Value = match serde_json::from_str(&strip_json_comments(content)) {
+        Ok(v) => v,
+        Err(e) => return vec![format!("invalid JSON: {}", e)],
+    };
+    let Some(extends) = value.as_object_mut().map(|m| m.remove("extends")) else {
+        return vec!["expected a JSON object".to_string()];
+    };
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    if let Err(e) = resolve_config_value(&mut value, base_dir) {
+        return vec![e];
+    }
+    let Value::Object(map) = value else {
+        return vec!["expected a JSON object".to_string()];
+    };
+    let mut errors = validate_object(map, "");
+    match extends {
+        None => {}
+        Some(Value::String(_)) => {
+            if let Err(e) = read_config_file(path) {
+                errors.push(e);
+            }
+        }
+        Some(_) => errors.push("extends: expected a file path".to_string()),
+    }
+    errors
+}
+
+/// Check the keys of a config object, and of each profile in it. `prefix`
+/// locates nested errors, e.g. `profiles.ci.`.
+fn validate_object(map: Map<String, Value>, prefix: &str) -> Vec<String> {
+    let keys = Config::keys();
+    let mut errors = Vec::new();
+    for (key, value) in map {
+        if !keys.contains(&key) {
+            errors.push(unknown_key(&format!("{}{}", prefix, key), &keys));
+            continue;
+        }
+        if key == "profiles" {
+            if let Value::Object(profiles) = &value {
+                for (name, profile) in profiles {
+                    let nested = format!("{}profiles.{}.", prefix, name);
+                    match profile {
+                        Value::Object(p) => errors.extend(validate_object(p.clone(), &nested)),
+                        _ => errors.push(format!(
+                            "{}: expected an object",
+                            nested.trim_end_matches('.')
+                        )),
+                    }
+                }
+                continue;
+            }
+        }
+        let single = Value::Object(Map::from_iter([(key.clone(), value)]));
+        if let Err(e) = serde_json::from_value::<Config>(single) {
+            errors.push(format!("{}{}: {}", prefix, key, e));
+        }
+    }
+    errors
+}
+
+/// Interpret a `config set` value for `key`: JSON first (`true`, `5000`,
+/// `["a"]`), then a plain string, then a comma-separated list.
+fn parse_value(key: &str, raw: &str) -> Result<Value, String> {
+    let mut candidates: Vec<Value> = serde_json::from_str(raw).into_iter().collect();
+    candidates.push(json!(raw));
+    candidates.push(json!(raw
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()));
+    let mut first_error = None;
+    for value in candidates {
+        let single = Value::Object(Map::from_iter([(key.to_string(), value.clone())]));
+        match serde_json::from_value::<Config>(single) {
+            Ok(_) => return Ok(value),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(format!(
+        "Invalid value for {}: {}",
+        key,
+        first_error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+fn user_config() -> Result<PathBuf, String> {
+    user_config_path().ok_or_else(|| "Cannot determine home directory".to_string())
+}
+
+fn check_key(key: &str) -> Result<(), String> {
+    let keys = Config::keys();
+    if keys.iter().any(|k| k == key) {
+        Ok(())
+    } else {
+        let mut msg = unknown_key(key, &keys);
+        msg[..1].make_ascii_uppercase();
+        Err(msg)
+    }
+}
+
+fn show(args: &[String], with_sources: bool, json_mode: bool) {
+    let settings = settings(args).unwrap_or_else(|e| fail(&e, json_mode));
+    let profile = selected_profile(args).ok().flatten();
+    if json_mode {
+        let map: Map<String, Value> = settings
+            .into_iter()
+            .filter(|s| with_sources || !s.value.is_null())
+            .map(|s| {
+                let entry = if with_sources {
+                    json!({ "value": s.value, "source": s.source })
+                } else {
+                    s.value
+                };
+                (s.key, entry)
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({ "success": true, "data": { "profile": profile, "settings": map } })
+        );
+        return;
+    }
+    if let Some(name) = profile {
+        println!("{}", color::dim(&format!("Profile: {}", name)));
+    }
+    let mut shown = 0;
+    for s in settings {
+        if s.value.is_null() && !with_sources {
+            continue;
+        }
+        shown += 1;
+        let value = match &s.value {
+            Value::Null => color::dim("(unset)"),
+            v => v.to_string(),
+        };
+        if with_sources {
+            println!(
+                "{} = {}  {}",
+                color::bold(&s.key),
+                value,
+                color::dim(&format!("({})", s.source))
+            );
+        } else {
+            println!("{} = {}", color::bold(&s.key), value);
+        }
+    }
+    if shown == 0 {
+        println!("{}", color::dim("No settings configured"));
+    }
+}
+
+fn run_validate(file: &str, json_mode: bool) {
+    let content = fs::read_to_string(file)
+        .unwrap_or_else(|e| fail(&format!("Cannot read {}: {}", file, e), json_mode));
+    let errors = validate(&content, Path::new(file));
+    if errors.is_empty() {
+        if json_mode {
+            println!(
+                "{}",
+                json!({ "success": true, "data": { "file": file, "valid": true } })
+            );
+        } else {
+            println!("{} {} is valid", color::success_indicator(), file);
+        }
+        return;
+    }
+    let msg = format!(
+        "{} has {} problem{}",
+        file,
+        errors.len(),
+        if errors.len() == 1 { "" } else { "s" }
+    );
+    if json_mode {
+        println!(
+            "{}",
+            json!({
+                "success": false,
+                "error": msg,
+                "data": { "file": file, "valid": false, "errors": errors },
+            })
+        );
+    } else {
+        eprintln!("{} {}", color::error_indicator(), msg);
+        for e in &errors {
+            eprintln!("  - {}", e);
+        }
+    }
+    exit(exit_code::USAGE);
+}
+
+fn init(project: bool, force: bool) -> Result<PathBuf, String> {
+    let path = if project {
+        PathBuf::from(PROJECT_CONFIG_FILENAME)
+    } else {
+        user_config()?
+    };
+    if path.exists() && !force {
+        return Err(format!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        ));
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, TEMPLATE).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+fn get(key: &str) -> Result<Value, String> {
+    check_key(key)?;
+    let path = user_config()?;
+    read_json(&path)
+        .and_then(|c| c.get(key).cloned())
+        .filter(|v| !v.is_null())
+        .ok_or_else(|| format!("{} is not set in {}", key, path.display()))
+}
+
+fn set(key: &str, raw: &str, force: bool) -> Result<(PathBuf, Value), String> {
+    check_key(key)?;
+    let value = parse_value(key, raw)?;
+    let path = user_config()?;
+    let mut map = match fs::read_to_string(&path) {
+        Ok(content) => {
+            let stripped = strip_json_comments(&content);
+            // The file is rewritten as plain JSON, which would drop the notes
+            // `config init` writes; only do that when asked to
+            if stripped != content && !force {
+                return Err(format!(
+                    "{} contains comments that config set would remove (edit it by hand, or use --force to rewrite it)",
+                    path.display()
+                ));
+            }
+            match serde_json::from_str(&stripped) {
+                Ok(Value::Object(map)) => map,
+                _ => return Err(format!("{} is not a valid config file", path.display())),
+            }
+        }
+        Err(_) => Map::new(),
+    };
+    map.insert(key.to_string(), value.clone());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(&map).unwrap_or_default() + "\n";
+    fs::write(&path, content).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    Ok((path, value))
+}
+
+/// Handle `config show|validate|init|get|set` without the daemon. `args` is
+/// the full command line, so `show` can tell which flags were given.
+pub fn run_config(args: &[String], clean: &[String], json_mode: bool) -> ! {
+    let has = |flag: &str| clean.iter().any(|a| a == flag);
+    match clean.get(1).map(String::as_str) {
+        Some("show") | None => show(args, has("--sources"), json_mode),
+        Some("validate") => match clean.get(2) {
+            Some(file) => run_validate(file, json_mode),
+            None => fail(
+                "Missing arguments for: config validate\nUsage: agent-browser config validate <file>",
+                json_mode,
+            ),
+        },
+        Some("init") => match init(has("--project"), has("--force")) {
+            Ok(path) => {
+                if json_mode {
+                    println!(
+                        "{}",
+                        json!({ "success": true, "data": { "path": path.display().to_string() } })
+                    );
+                } else {
+                    println!("{} Wrote {}", color::success_indicator(), path.display());
+                }
+            }
+            Err(e) => fail(&e, json_mode),
+        },
+        Some("get") => {
+            let Some(key) = clean.get(2) else {
+                fail(
+                    "Missing arguments for: config get\nUsage: agent-browser config get <key>",
+                    json_mode,
+                );
+            };
+            match get(key) {
+                Ok(value) => {
+                    if json_mode {
+                        println!(
+                            "{}",
+                            json!({ "success": true, "data": { "key": key, "value": value } })
+                        );
+                    } else if let Value::String(s) = value {
+                        println!("{}", s);
+                    } else {
+                        println!("{}", value);
+                    }
+                }
+                Err(e) => fail(&e, json_mode),
+            }
+        }
+        Some("set") => {
+            let operands: Vec<&String> = clean[2..].iter().filter(|a| *a != "--force").collect();
+            let (Some(key), Some(raw)) = (operands.first(), operands.get(1)) else {
+                fail(
+                    "Missing arguments for: config set\nUsage: agent-browser config set <key> <value>",
+                    json_mode,
+                );
+            };
+            match set(key, raw, has("--force")) {
+                Ok((path, value)) => {
+                    if json_mode {
+                        println!(
+                            "{}",
+                            json!({
+                                "success": true,
+                                "data": { "key": key, "value": value, "path": path.display().to_string() },
+                            })
+                        );
+                    } else {
+                        println!(
+                            "{} {} = {} {}",
+                            color::success_indicator(),
+                            key,
+                            value,
+                            color::dim(&format!("({})", path.display()))
+                        );
+                    }
+                }
+                Err(e) => fail(&e, json_mode),
+            }
+        }
+        Some(other) => fail(
+            &format!(
+                "Unknown subcommand: {}. Valid options: {}",
+                other,
+                registry::subcommand_names("config").join(", ")
+            ),
+            json_mode,
+        ),
+    }
+    exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_template_is_valid() {
+        assert!(validate(TEMPLATE, Path::new("agent-browser.json")).is_empty());
+        // Every commented-out setting in the template is a real key
+        let uncommented: String = TEMPLATE
+            .lines()
+            .map(|l| {
+                let l = l.trim_start();
+                match l.strip_prefix("// \"") {
+                    Some(rest) if !rest.starts_with("login") => format!("\"{}", rest),
+                    _ => l.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let keys = Config::keys();
+        for line in uncommented.lines().filter(|l| l.starts_with('"')) {
+            let key = line.split('"').nth(1).unwrap();
+            assert!(
+                key == "extends" || keys.contains(&key.to_string()),
+                "unknown key {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_strip_json_comments() {
+        let stripped = strip_json_comments(
+            "{\n  // note\n  \"url\": \"http://a//b\", /* inline */ \"x\": \"\\\"//\"\n}",
+        );
+        let value: serde_json::Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["url"], "http://a//b");
+        assert_eq!(value["x"], "\"//");
+        assert_eq!(stripped.lines().count(), 4);
+        // config set relies on this to notice the template's comments
+        assert_ne!(strip_json_comments(TEMPLATE), TEMPLATE);
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_keys_and_bad_types() {
+        let errors = validate(
+            r#"{ "headed": "yes", "max_output": 5, "bogus": 1, "session": "a" }"#,
+            Path::new("agent-browser.json"),
+        );
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("headed: invalid type")));
+        assert!(
+            errors.contains(&"unknown key 'max_output' (did you mean 'maxOutput'?)".to_string())
+        );
+        assert!(errors.contains(&"unknown key 'bogus'".to_string()));
+        assert_eq!(
+            validate("[1]", Path::new("agent-browser.json")),
+            vec!["expected a JSON object"]
+        );
+        let errors = validate(
+            r#"{ "profiles": { "ci": { "headed": 1, "proxy_list": "x" }, "dev": 2 } }"#,
+            Path::new("agent-browser.json"),
+        );
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("profiles.ci.headed: invalid type")));
+        assert!(errors.contains(
+            &"unknown key 'profiles.ci.proxy_list' (did you mean 'proxyList'?)".to_string()
+        ));
+        assert!(errors.contains(&"profiles.dev: expected an object".to_string()));
+        let errors = validate(
+            r#"{ "proxy": "${AB_TEST_VALIDATE_UNSET}" }"#,
+            Path::new("agent-browser.json"),
+        );
+        assert!(errors[0].starts_with("proxy: environment variable AB_TEST_VALIDATE_UNSET"));
+        assert_eq!(
+            validate(r#"{ "extends": 1 }"#, Path::new("agent-browser.json")),
+            vec!["extends: expected a file path"]
+        );
+        assert!(validate("{ nope", Path::new("agent-browser.json"))
+            .first()
+            .unwrap()
+            .starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn test_flag_and_env_names() {
+        assert_eq!(flag_for("maxOutput").map(|o| o.name), Some("--max-output"));
+        assert_eq!(flag_for("extensions").map(|o| o.name), Some("--extension"));
+        assert_eq!(
+            flag_for("clientCerts").map(|o| o.name),
+            Some("--client-cert")
+        );
+        assert!(flag_for("aliases").is_none());
+        assert_eq!(
+            env_for("ignoreHttpsErrors").unwrap(),
+            "AGENT_BROWSER_IGNORE_HTTPS_ERRORS"
+        );
+        assert_eq!(env_for("device").unwrap(), "AGENT_BROWSER_IOS_DEVICE");
+        assert!(env_for("cdp").is_none());
+    }
+
+    #[test]
+    fn test_flag_value() {
+        let headed = registry::global_option("--headed").unwrap();
+        let ext = registry::global_option("--extension").unwrap();
+        let a = args("--session --headed --headed false --extension a --extension b open");
+        // "--headed" after --session is its value, not the flag
+        assert_eq!(flag_value(&a, headed), Some(json!(false)));
+        assert_eq!(flag_value(&a, ext), Some(json!(["a", "b"])));
+        assert_eq!(flag_value(&args("open x"), headed), None);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("headed", "true").unwrap(), json!(true));
+        assert_eq!(parse_value("maxOutput", "5000").unwrap(), json!(5000));
+        // Numbers and booleans stay strings where a string is expected
+        assert_eq!(parse_value("session", "123").unwrap(), json!("123"));
+        assert_eq!(
+            parse_value("allowedDomains", "a.com, b.com").unwrap(),
+            json!(["a.com", "b.com"])
+        );
+        assert!(parse_value("maxOutput", "lots").is_err());
+    }
+}
@@ -1,13 +1,16 @@
+use crate::debug;
+use crate::flags::ClientCert;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::env;
 use std::fs;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
@@ -26,6 +29,9 @@ pub struct Response {
     pub success: bool,
     pub data: Option<Value>,
     pub error: Option<String>,
+    /// Failure class set by the daemon, mapped to an exit code by `exit_code`
+    #[serde(rename = "errorCode", default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -116,10 +122,16 @@ fn get_pid_path(session: &str) -> PathBuf {
     get_socket_dir().join(format!("{}.pid", session))
 }
 
-/// Clean up stale socket and PID files for a session
+/// Slowest timeout the running daemon was given with `set timeout`.
+fn get_timeout_path(session: &str) -> PathBuf {
+    get_socket_dir().join(format!("{}.timeout", session))
+}
+
+/// Clean up stale socket, PID and timeout files for a session
 fn cleanup_stale_files(session: &str) {
     let pid_path = get_pid_path(session);
     let _ = fs::remove_file(&pid_path);
+    let _ = fs::remove_file(get_timeout_path(session));
 
     #[cfg(unix)]
     {
@@ -150,8 +162,21 @@ fn get_port_for_session(session: &str) -> u16 {
     49152 + ((hash.unsigned_abs() as u32 % 16383) as u16)
 }
 
+/// Where clients reach a session's daemon: the socket path on Unix,
+/// `127.0.0.1:<port>` on Windows.
+pub fn daemon_address(session: &str) -> String {
+    #[cfg(unix)]
+    {
+        get_socket_path(session).display().to_string()
+    }
+    #[cfg(windows)]
+    {
+        format!("127.0.0.1:{}", get_port_for_session(session))
+    }
+}
+
 #[cfg(unix)]
-fn is_daemon_running(session: &str) -> bool {
+pub fn is_daemon_running(session: &str) -> bool {
     let pid_path = get_pid_path(session);
     if !pid_path.exists() {
         return false;
@@ -167,7 +192,7 @@ fn is_daemon_running(session: &str) -> bool {
 }
 
 #[cfg(windows)]
-fn is_daemon_running(session: &str) -> bool {
+pub fn is_daemon_running(session: &str) -> bool {
     let pid_path = get_pid_path(session);
     if !pid_path.exists() {
         return false;
@@ -180,6 +205,22 @@ fn is_daemon_running(session: &str) -> bool {
     .is_ok()
 }
 
+/// Sessions with a running daemon, sorted by name.
+pub fn list_sessions() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(get_socket_dir()) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().strip_suffix(".pid")?.to_string();
+            (!name.is_empty() && is_daemon_running(&name)).then_some(name)
+        })
+        .collect();
+    sessions.sort();
+    sessions
+}
+
 fn daemon_ready(session: &str) -> bool {
     #[cfg(unix)]
     {
@@ -215,6 +256,7 @@ pub struct DaemonOptions<'a> {
     pub user_agent: Option<&'a str>,
     pub proxy: Option<&'a str>,
     pub proxy_bypass: Option<&'a str>,
+    pub proxy_pac: Option<&'a str>,
     pub ignore_https_errors: bool,
     pub allow_file_access: bool,
     pub profile: Option<&'a str>,
@@ -224,8 +266,16 @@ pub struct DaemonOptions<'a> {
     pub session_name: Option<&'a str>,
     pub download_path: Option<&'a str>,
     pub allowed_domains: Option<&'a [String]>,
+    pub block: Option<&'a [String]>,
+    pub ca_certs: &'a [String],
+    pub resolve: &'a [String],
+    pub client_certs: &'a [ClientCert],
     pub action_policy: Option<&'a str>,
     pub confirm_actions: Option<&'a str>,
+    pub default_timeout: Option<u64>,
+    pub navigation_timeout: Option<u64>,
+    pub action_timeout: Option<u64>,
+    pub wait_timeout: Option<u64>,
 }
 
 fn apply_daemon_env(cmd: &mut Command, session: &str, opts: &DaemonOptions) {
@@ -253,6 +303,9 @@ fn apply_daemon_env(cmd: &mut Command, session: &str, opts: &DaemonOptions) {
     if let Some(pb) = opts.proxy_bypass {
         cmd.env("AGENT_BROWSER_PROXY_BYPASS", pb);
     }
+    if let Some(pac) = opts.proxy_pac {
+        cmd.env("AGENT_BROWSER_PROXY_PAC", pac);
+    }
     if opts.ignore_https_errors {
         cmd.env("AGENT_BROWSER_IGNORE_HTTPS_ERRORS", "1");
     }
@@ -280,20 +333,82 @@ fn apply_daemon_env(cmd: &mut Command, session: &str, opts: &DaemonOptions) {
     if let Some(ad) = opts.allowed_domains {
         cmd.env("AGENT_BROWSER_ALLOWED_DOMAINS", ad.join(","));
     }
+    if let Some(b) = opts.block {
+        cmd.env("AGENT_BROWSER_BLOCK", b.join(","));
+    }
+    if !opts.ca_certs.is_empty() {
+        cmd.env("AGENT_BROWSER_CA_CERTS", opts.ca_certs.join(","));
+    }
+    if !opts.resolve.is_empty() {
+        cmd.env("AGENT_BROWSER_RESOLVE", opts.resolve.join(","));
+    }
+    if !opts.client_certs.is_empty() {
+        if let Ok(certs) = serde_json::to_string(opts.client_certs) {
+            cmd.env("AGENT_BROWSER_CLIENT_CERTS", certs);
+        }
+    }
     if let Some(ap) = opts.action_policy {
         cmd.env("AGENT_BROWSER_ACTION_POLICY", ap);
     }
     if let Some(ca) = opts.confirm_actions {
         cmd.env("AGENT_BROWSER_CONFIRM_ACTIONS", ca);
     }
+    if let Some(ms) = opts.default_timeout {
+        cmd.env("AGENT_BROWSER_DEFAULT_TIMEOUT", ms.to_string());
+    }
+    if let Some(ms) = opts.navigation_timeout {
+        cmd.env("AGENT_BROWSER_NAVIGATION_TIMEOUT", ms.to_string());
+    }
+    if let Some(ms) = opts.action_timeout {
+        cmd.env("AGENT_BROWSER_ACTION_TIMEOUT", ms.to_string());
+    }
+    if let Some(ms) = opts.wait_timeout {
+        cmd.env("AGENT_BROWSER_WAIT_TIMEOUT", ms.to_string());
+    }
+}
+
+/// Trace the daemon command line and the environment it is given, with
+/// credentials redacted.
+fn trace_spawn(cmd: &Command) {
+    if !debug::enabled() {
+        return;
+    }
+    let env: Vec<(String, String)> = cmd
+        .get_envs()
+        .filter_map(|(k, v)| {
+            Some((k.to_string_lossy().into_owned(), v?.to_string_lossy().into_owned()))
+        })
+        .collect();
+    debug::log(
+        "daemon.spawn",
+        json!({
+            "program": cmd.get_program().to_string_lossy(),
+            "args": cmd.get_args().map(|a| a.to_string_lossy()).collect::<Vec<_>>(),
+            "env": debug::redact_env(env.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+        }),
+    );
 }
 
 pub fn ensure_daemon(
     session: &str,
     opts: &DaemonOptions,
 ) -> Result<DaemonResult, String> {
+    let running = is_daemon_running(session);
+    let ready = running && daemon_ready(session);
+    debug::log(
+        "daemon.check",
+        json!({
+            "session": session,
+            "socketDir": get_socket_dir().display().to_string(),
+            "pidFile": get_pid_path(session).display().to_string(),
+            "address": daemon_address(session),
+            "running": running,
+            "ready": ready,
+        }),
+    );
+
     // Check if daemon is running AND responsive
-    if is_daemon_running(session) && daemon_ready(session) {
+    if ready {
         // Double-check it's actually responsive by waiting and checking again
         // This handles the race condition where daemon is shutting down
         // (daemon has a 100ms shutdown delay, so we wait longer)
@@ -364,6 +479,14 @@ pub fn ensure_daemon(
         daemon_paths.insert(1, home_path.join("daemon.js"));
     }
 
+    if debug::enabled() {
+        let tried: Vec<Value> = daemon_paths
+            .iter()
+            .map(|p| json!({ "path": p.display().to_string(), "exists": p.exists() }))
+            .collect();
+        debug::log("daemon.discover", json!({ "candidates": tried }));
+    }
+
     let daemon_path = daemon_paths
         .iter()
         .find(|p| p.exists())
@@ -377,6 +500,7 @@ pub fn ensure_daemon(
         let mut cmd = Command::new("node");
         cmd.arg(daemon_path);
         apply_daemon_env(&mut cmd, session, opts);
+        trace_spawn(&cmd);
 
         // Create new process group and session to fully detach
         unsafe {
@@ -400,13 +524,10 @@ pub fn ensure_daemon(
 
         // On Windows, call node directly. Command::new handles PATH resolution (node.exe or node.cmd)
         // and automatically quotes arguments containing spaces.
-        eprintln!("[DEBUG] Starting daemon: node {:?}", daemon_path);
-        eprintln!("[DEBUG] Session: {}", session);
-        eprintln!("[DEBUG] Port: {}", get_port_for_session(session));
-        
         let mut cmd = Command::new("node");
         cmd.arg(daemon_path);
         apply_daemon_env(&mut cmd, session, opts);
+        trace_spawn(&cmd);
 
         // CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS
         const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
@@ -420,8 +541,13 @@ pub fn ensure_daemon(
             .map_err(|e| format!("Failed to start daemon: {}", e))?;
     }
 
+    let started = Instant::now();
     for _ in 0..50 {
         if daemon_ready(session) {
+            debug::log(
+                "daemon.ready",
+                json!({ "session": session, "ms": debug::elapsed_ms(started) }),
+            );
             return Ok(DaemonResult {
                 already_running: false,
             });
@@ -470,9 +596,13 @@ pub fn send_command(cmd: Value, session: &str) -> Result<Response, String> {
         }
 
         match send_command_once(&cmd, session) {
-            Ok(response) => return Ok(response),
+            Ok(response) => {
+                remember_daemon_timeout(&cmd, &response, session);
+                return Ok(response);
+            }
             Err(e) => {
                 if is_transient_error(&e) {
+                    trace_retry(session, attempt, MAX_RETRIES, &e);
                     last_error = e;
                     continue;
                 }
@@ -488,6 +618,50 @@ pub fn send_command(cmd: Value, session: &str) -> Result<Response, String> {
     ))
 }
 
+fn trace_retry(session: &str, attempt: u32, max: u32, error: &str) {
+    debug::log(
+        "retry",
+        json!({
+            "session": session,
+            "attempt": attempt + 1,
+            "maxAttempts": max,
+            "error": error,
+            "retrying": attempt + 1 < max,
+        }),
+    );
+}
+
+/// Trace an outgoing request frame. Returns the time it was sent.
+fn trace_request(session: &str, cmd: &Value) -> Instant {
+    debug::log(
+        "request",
+        json!({ "session": session, "frame": debug::redact(cmd) }),
+    );
+    Instant::now()
+}
+
+/// Trace the response frame (or failure) for a request sent at `sent`.
+fn trace_response(session: &str, sent: Instant, result: &Result<Response, String>) {
+    if !debug::enabled() {
+        return;
+    }
+    let ms = debug::elapsed_ms(sent);
+    match result {
+        Ok(response) => debug::log(
+            "response",
+            json!({
+                "session": session,
+                "ms": ms,
+                "frame": debug::redact(&serde_json::to_value(response).unwrap_or_default()),
+            }),
+        ),
+        Err(e) => debug::log(
+            "response",
+            json!({ "session": session, "ms": ms, "error": e }),
+        ),
+    }
+}
+
 /// Check if an error is transient and worth retrying.
 /// Transient errors include:
 /// - EAGAIN/EWOULDBLOCK (os error 35 on macOS, 11 on Linux)
@@ -510,10 +684,70 @@ fn is_transient_error(error: &str) -> bool {
         || error.contains("os error 111") // Connection refused (Linux)
 }
 
+/// How long to wait for a daemon response, in milliseconds.
+static RESPONSE_TIMEOUT_MS: AtomicU64 = AtomicU64::new(30_000);
+
+/// Allow responses to take as long as the slowest configured browser
+/// timeout, plus a margin for the daemon to report the failure. Only ever
+/// raises the limit, so flags and the daemon's own timeouts combine.
+pub fn set_response_timeout(operation_ms: u64) {
+    RESPONSE_TIMEOUT_MS.fetch_max(operation_ms.saturating_add(5_000), Ordering::Relaxed);
+}
+
+/// Wait long enough for timeouts set on the running daemon with
+/// `set timeout` by an earlier invocation.
+pub fn load_daemon_timeout(session: &str) {
+    let saved = fs::read_to_string(get_timeout_path(session)).ok();
+    if let Some(ms) = saved.and_then(|s| s.trim().parse().ok()) {
+        set_response_timeout(ms);
+    }
+}
+
+/// After `set timeout`, keep the daemon's slowest timeout for this and later
+/// invocations. The file is removed when a new daemon starts.
+fn remember_daemon_timeout(cmd: &Value, response: &Response, session: &str) {
+    if cmd.get("action").and_then(|v| v.as_str()) != Some("timeout") || !response.success {
+        return;
+    }
+    let slowest = response
+        .data
+        .as_ref()
+        .and_then(|d| d.get("slowest"))
+        .or_else(|| cmd.get("timeout"))
+        .and_then(|v| v.as_u64());
+    if let Some(ms) = slowest {
+        set_response_timeout(ms);
+        let _ = fs::write(get_timeout_path(session), ms.to_string());
+    }
+}
+
+/// Read timeout for one command: the configured limit, raised for commands
+/// that carry a longer `timeout` of their own (e.g. `wait --timeout`).
+fn response_timeout(cmd: &Value) -> Duration {
+    read_timeout(cmd, RESPONSE_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// `limit_ms`, or the command's own `timeout` plus a margin for the daemon
+/// to report the failure, whichever is longer.
+pub fn read_timeout(cmd: &Value, limit_ms: u64) -> Duration {
+    let own = cmd
+        .get("timeout")
+        .and_then(|v| v.as_u64())
+        .map_or(0, |ms| ms.saturating_add(5_000));
+    Duration::from_millis(limit_ms.max(own))
+}
+
 fn send_command_once(cmd: &Value, session: &str) -> Result<Response, String> {
+    let sent = trace_request(session, cmd);
+    let result = send_frame(cmd, session);
+    trace_response(session, sent, &result);
+    result
+}
+
+fn send_frame(cmd: &Value, session: &str) -> Result<Response, String> {
     let mut stream = connect(session)?;
 
-    stream.set_read_timeout(Some(Duration::from_secs(30))).ok();
+    stream.set_read_timeout(Some(response_timeout(cmd))).ok();
     stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
 
     let mut json_str = serde_json::to_string(cmd).map_err(|e| e.to_string())?;
@@ -532,6 +766,91 @@ fn send_command_once(cmd: &Value, session: &str) -> Result<Response, String> {
     serde_json::from_str(&response_line).map_err(|e| format!("Invalid response: {}", e))
 }
 
+/// A persistent daemon connection for sending a sequence of commands, as
+/// workflows do. Reconnects on transient errors like `send_command`, but never
+/// retries a command whose response timed out, since it may have executed.
+pub struct DaemonClient {
+    session: String,
+    stream: Option<BufReader<Connection>>,
+}
+
+impl DaemonClient {
+    pub fn new(session: &str) -> Self {
+        Self {
+            session: session.to_string(),
+            stream: None,
+        }
+    }
+
+    pub fn send(&mut self, cmd: &Value, timeout: Duration) -> Result<Response, String> {
+        const MAX_RETRIES: u32 = 5;
+        const RETRY_DELAY_MS: u64 = 200;
+
+        let mut last_error = String::new();
+        for attempt in 0..MAX_RETRIES {
+            if attempt > 0 {
+                thread::sleep(Duration::from_millis(RETRY_DELAY_MS * (attempt as u64)));
+            }
+            match self.send_once(cmd, timeout) {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    // The stream may hold a late response now; never reuse it
+                    self.stream = None;
+                    if e.starts_with("Timed out") || !is_transient_error(&e) {
+                        return Err(e);
+                    }
+                    trace_retry(&self.session, attempt, MAX_RETRIES, &e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(format!(
+            "{} (after {} retries - daemon may be busy or unresponsive)",
+            last_error, MAX_RETRIES
+        ))
+    }
+
+    fn send_once(&mut self, cmd: &Value, timeout: Duration) -> Result<Response, String> {
+        let sent = trace_request(&self.session, cmd);
+        let result = self.send_frame(cmd, timeout);
+        trace_response(&self.session, sent, &result);
+        result
+    }
+
+    fn send_frame(&mut self, cmd: &Value, timeout: Duration) -> Result<Response, String> {
+        if self.stream.is_none() {
+            let stream = connect(&self.session)?;
+            stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
+            self.stream = Some(BufReader::new(stream));
+        }
+        let reader = self.stream.as_mut().expect("connected above");
+        reader.get_ref().set_read_timeout(Some(timeout)).ok();
+
+        let mut json_str = serde_json::to_string(cmd).map_err(|e| e.to_string())?;
+        json_str.push('\n');
+        reader
+            .get_mut()
+            .write_all(json_str.as_bytes())
+            .map_err(|e| format!("Failed to send: {}", e))?;
+
+        let mut response_line = String::new();
+        match reader.read_line(&mut response_line) {
+            Ok(0) => return Err("Failed to read: EOF".to_string()),
+            Ok(_) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                return Err(format!("Timed out after {}ms", timeout.as_millis()));
+            }
+            Err(e) => return Err(format!("Failed to read: {}", e)),
+        }
+        serde_json::from_str(&response_line).map_err(|e| format!("Invalid response: {}", e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -645,6 +964,16 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_response_timeout_follows_command_timeout() {
+        let floor = Duration::from_millis(RESPONSE_TIMEOUT_MS.load(Ordering::Relaxed));
+        assert_eq!(response_timeout(&json!({ "action": "wait" })), floor);
+        assert_eq!(
+            response_timeout(&json!({ "action": "wait", "timeout": 600_000 })),
+            Duration::from_millis(605_000)
+        );
+    }
+
     #[test]
     fn test_is_transient_error_would_block() {
         assert!(is_transient_error("operation WouldBlock"));
@@ -0,0 +1,99 @@
+use crate::color;
+use crate::exit_code;
+use crate::flags::Flags;
+use crate::validation::{validate_ca_cert, validate_client_cert, validate_resolve_entry};
+use serde_json::json;
+
+/// Result of validating one launch-time setting that references local files
+/// or needs a specific syntax.
+pub struct Check {
+    pub category: &'static str,
+    pub subject: String,
+    pub detail: Option<String>,
+    pub error: Option<String>,
+}
+
+const CLIENT_CERTS: &str = "Client certificates";
+const CA_CERTS: &str = "CA certificates";
+const RESOLVE: &str = "Resolver overrides";
+
+/// Validate client certificates, CA certificates and resolver overrides.
+/// Used both by `doctor` and as a pre-launch check in main.
+pub fn launch_checks(flags: &Flags) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for cert in &flags.client_certs {
+        checks.push(Check {
+            category: CLIENT_CERTS,
+            subject: cert.origin.clone(),
+            detail: Some(format!("cert: {}, key: {}", cert.cert, cert.key)),
+            error: validate_client_cert(cert).err(),
+        });
+    }
+    for path in &flags.ca_certs {
+        checks.push(Check {
+            category: CA_CERTS,
+            subject: path.clone(),
+            detail: None,
+            error: validate_ca_cert(path).err(),
+        });
+    }
+    for entry in &flags.resolve {
+        checks.push(Check {
+            category: RESOLVE,
+            subject: entry.clone(),
+            detail: None,
+            error: validate_resolve_entry(entry).err(),
+        });
+    }
+    checks
+}
+
+/// Print a report of launch configuration checks. Exits non-zero if any failed.
+pub fn run_doctor(flags: &Flags) -> ! {
+    let checks = launch_checks(flags);
+    let ok = checks.iter().all(|c| c.error.is_none());
+
+    if flags.json {
+        let items: Vec<_> = checks
+            .iter()
+            .map(|c| {
+                json!({
+                    "category": c.category,
+                    "subject": c.subject,
+                    "ok": c.error.is_none(),
+                    "error": c.error,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({ "success": ok, "data": { "checks": items } })
+        );
+    } else {
+        for category in [CLIENT_CERTS, CA_CERTS, RESOLVE] {
+            println!("{}:", color::bold(category));
+            let mut any = false;
+            for check in checks.iter().filter(|c| c.category == category) {
+                any = true;
+                match (&check.error, &check.detail) {
+                    (Some(e), _) => {
+                        println!("  {} {}: {}", color::error_indicator(), check.subject, e)
+                    }
+                    (None, Some(d)) => println!(
+                        "  {} {} {}",
+                        color::success_indicator(),
+                        check.subject,
+                        color::dim(&format!("({})", d))
+                    ),
+                    (None, None) => println!("  {} {}", color::success_indicator(), check.subject),
+                }
+            }
+            if !any {
+                println!("  {}", color::dim("none configured"));
+            }
+        }
+    }
+
+    // Every check is of a configured file or value
+    std::process::exit(if ok { 0 } else { exit_code::USAGE });
+}
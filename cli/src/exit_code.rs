@@ -0,0 +1,114 @@
+//! Process exit codes by failure class, so scripts can branch on why a
+//! command failed without parsing its message.
+
+use serde_json::json;
+
+use crate::connection::Response;
+
+/// Any failure without a more specific code, e.g. a page error, a file that
+/// could not be written, or `install` and the auth helper failing
+pub const ERROR: i32 = 1;
+/// Unknown command, missing or invalid arguments, conflicting flags, an
+/// invalid config, workflow or certificate file, a `--field` path missing
+/// from the response, or a plugin that cannot be run
+pub const USAGE: i32 = 2;
+/// The daemon could not be started or reached
+pub const CONNECTION: i32 = 3;
+/// A navigation, action or wait timed out
+pub const TIMEOUT: i32 = 4;
+/// The selector or ref matched no element
+pub const NOT_FOUND: i32 = 5;
+/// The action policy or an interactive confirmation denied the action
+pub const POLICY_DENIED: i32 = 6;
+/// An `assert` check did not hold
+pub const ASSERTION_FAILED: i32 = 7;
+
+fn is_assertion_failure(resp: &Response) -> bool {
+    resp.data.as_ref().and_then(|d| d.get("passed")) == Some(&json!(false))
+}
+
+/// Exit code for a daemon `errorCode`.
+pub fn from_error_code(code: &str) -> i32 {
+    match code {
+        "usage" => USAGE,
+        "timeout" => TIMEOUT,
+        "not_found" => NOT_FOUND,
+        "policy_denied" => POLICY_DENIED,
+        "assertion_failed" => ASSERTION_FAILED,
+        _ => ERROR,
+    }
+}
+
+/// Exit code for a response, 0 when it succeeded.
+pub fn for_response(resp: &Response) -> i32 {
+    if resp.success {
+        return 0;
+    }
+    match resp.error_code.as_deref() {
+        Some(code) => from_error_code(code),
+        // Daemons that predate `errorCode` still mark failed assertions
+        None if is_assertion_failure(resp) => ASSERTION_FAILED,
+        None => ERROR,
+    }
+}
+
+/// Exit code for a failure to talk to the daemon. A read that outlasts the
+/// response timeout is a timeout, everything else a connection failure.
+pub fn for_send_error(error: &str) -> i32 {
+    let error = error.to_lowercase();
+    if error.contains("timed out")
+        || error.contains("would block")
+        || error.contains("temporarily unavailable")
+    {
+        TIMEOUT
+    } else {
+        CONNECTION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(error_code: Option<&str>, data: Option<serde_json::Value>) -> Response {
+        Response {
+            success: false,
+            data,
+            error: Some("failed".to_string()),
+            error_code: error_code.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_for_response() {
+        assert_eq!(
+            for_response(&Response {
+                success: true,
+                ..Default::default()
+            }),
+            0
+        );
+        assert_eq!(for_response(&failure(Some("timeout"), None)), TIMEOUT);
+        assert_eq!(for_response(&failure(Some("not_found"), None)), NOT_FOUND);
+        assert_eq!(
+            for_response(&failure(Some("policy_denied"), None)),
+            POLICY_DENIED
+        );
+        assert_eq!(for_response(&failure(Some("usage"), None)), USAGE);
+        assert_eq!(for_response(&failure(Some("something_new"), None)), ERROR);
+        assert_eq!(for_response(&failure(None, None)), ERROR);
+        assert_eq!(
+            for_response(&failure(None, Some(json!({ "passed": false })))),
+            ASSERTION_FAILED
+        );
+    }
+
+    #[test]
+    fn test_for_send_error() {
+        assert_eq!(
+            for_send_error("Failed to read: Resource temporarily unavailable (os error 11)"),
+            TIMEOUT
+        );
+        assert_eq!(for_send_error("Daemon failed to start"), CONNECTION);
+    }
+}
@@ -1,14 +1,84 @@
 use crate::color;
-use serde::Deserialize;
+use crate::exit_code;
+use crate::registry;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 const CONFIG_DIR: &str = ".agent-browser";
 const CONFIG_FILENAME: &str = "config.json";
-const PROJECT_CONFIG_FILENAME: &str = "agent-browser.json";
+pub const PROJECT_CONFIG_FILENAME: &str = "agent-browser.json";
 
-#[derive(Debug, Default, Deserialize)]
+/// A client certificate (mTLS) presented to a single origin.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClientCert {
+    pub origin: String,
+    pub cert: String,
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase_env: Option<String>,
+}
+
+impl ClientCert {
+    /// Parse a `--client-cert` value: `origin=<url>,cert=<pem>,key=<pem>[,passphrase-env=<VAR>]`.
+    pub fn parse(spec: &str) -> Result<ClientCert, String> {
+        let mut cert = ClientCert::default();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (k, v) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid --client-cert entry '{}': expected key=value", part))?;
+            match k.trim() {
+                "origin" => cert.origin = v.trim().to_string(),
+                "cert" => cert.cert = v.trim().to_string(),
+                "key" => cert.key = v.trim().to_string(),
+                "passphrase-env" | "passphraseEnv" => {
+                    cert.passphrase_env = Some(v.trim().to_string())
+                }
+                other => {
+                    return Err(format!(
+                        "Unknown --client-cert key '{}'. Valid keys: origin, cert, key, passphrase-env",
+                        other
+                    ))
+                }
+            }
+        }
+        if cert.origin.is_empty() || cert.cert.is_empty() || cert.key.is_empty() {
+            return Err(format!(
+                "Invalid --client-cert '{}': origin, cert and key are required",
+                spec
+            ));
+        }
+        Ok(cert)
+    }
+
+    /// Parse the JSON array in `AGENT_BROWSER_CLIENT_CERTS`.
+    pub fn parse_list(json: &str) -> Result<Vec<ClientCert>, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid AGENT_BROWSER_CLIENT_CERTS: {}", e))
+    }
+}
+
+/// A user-defined command: one command line or a list run in order.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Alias {
+    Command(String),
+    Steps(Vec<String>),
+}
+
+impl Alias {
+    pub fn steps(&self) -> Vec<String> {
+        match self {
+            Alias::Command(line) => vec![line.clone()],
+            Alias::Steps(lines) => lines.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Config {
     pub headed: Option<bool>,
@@ -23,6 +93,10 @@ pub struct Config {
     pub state: Option<String>,
     pub proxy: Option<String>,
     pub proxy_bypass: Option<String>,
+    pub proxy_pac: Option<String>,
+    pub proxy_password_env: Option<String>,
+    pub proxy_list: Option<String>,
+    pub proxy_rotate: Option<bool>,
     pub args: Option<String>,
     pub user_agent: Option<String>,
     pub provider: Option<String>,
@@ -36,14 +110,36 @@ pub struct Config {
     pub color_scheme: Option<String>,
     pub download_path: Option<String>,
     pub content_boundaries: Option<bool>,
+    pub injection_scan: Option<String>,
     pub max_output: Option<usize>,
+    pub max_tokens: Option<usize>,
+    pub default_timeout: Option<u64>,
+    pub navigation_timeout: Option<u64>,
+    pub action_timeout: Option<u64>,
+    pub wait_timeout: Option<u64>,
     pub allowed_domains: Option<Vec<String>>,
+    pub block: Option<Vec<String>>,
+    pub ca_certs: Option<Vec<String>>,
+    pub resolve: Option<Vec<String>>,
+    pub client_certs: Option<Vec<ClientCert>>,
     pub action_policy: Option<String>,
     pub confirm_actions: Option<String>,
     pub confirm_interactive: Option<bool>,
+    pub report: Option<String>,
+    pub aliases: Option<BTreeMap<String, Alias>>,
+    /// Named overrides, one applied with `--env <name>`
+    pub profiles: Option<BTreeMap<String, Config>>,
 }
 
 impl Config {
+    /// Every key a config file may set, sorted.
+    pub fn keys() -> Vec<String> {
+        match serde_json::to_value(Config::default()) {
+            Ok(Value::Object(map)) => map.into_iter().map(|(k, _)| k).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     fn merge(self, other: Config) -> Config {
         Config {
             headed: other.headed.or(self.headed),
@@ -64,6 +160,10 @@ impl Config {
             state: other.state.or(self.state),
             proxy: other.proxy.or(self.proxy),
             proxy_bypass: other.proxy_bypass.or(self.proxy_bypass),
+            proxy_pac: other.proxy_pac.or(self.proxy_pac),
+            proxy_password_env: other.proxy_password_env.or(self.proxy_password_env),
+            proxy_list: other.proxy_list.or(self.proxy_list),
+            proxy_rotate: other.proxy_rotate.or(self.proxy_rotate),
             args: other.args.or(self.args),
             user_agent: other.user_agent.or(self.user_agent),
             provider: other.provider.or(self.provider),
@@ -77,29 +177,307 @@ impl Config {
             color_scheme: other.color_scheme.or(self.color_scheme),
             download_path: other.download_path.or(self.download_path),
             content_boundaries: other.content_boundaries.or(self.content_boundaries),
+            injection_scan: other.injection_scan.or(self.injection_scan),
             max_output: other.max_output.or(self.max_output),
+            max_tokens: other.max_tokens.or(self.max_tokens),
+            default_timeout: other.default_timeout.or(self.default_timeout),
+            navigation_timeout: other.navigation_timeout.or(self.navigation_timeout),
+            action_timeout: other.action_timeout.or(self.action_timeout),
+            wait_timeout: other.wait_timeout.or(self.wait_timeout),
             allowed_domains: other.allowed_domains.or(self.allowed_domains),
+            block: other.block.or(self.block),
+            ca_certs: other.ca_certs.or(self.ca_certs),
+            resolve: other.resolve.or(self.resolve),
+            client_certs: other.client_certs.or(self.client_certs),
             action_policy: other.action_policy.or(self.action_policy),
             confirm_actions: other.confirm_actions.or(self.confirm_actions),
             confirm_interactive: other.confirm_interactive.or(self.confirm_interactive),
+            report: other.report.or(self.report),
+            aliases: match (self.aliases, other.aliases) {
+                (Some(mut a), Some(b)) => {
+                    a.extend(b);
+                    Some(a)
+                }
+                (a, b) => b.or(a),
+            },
+            profiles: match (self.profiles, other.profiles) {
+                (Some(mut a), Some(b)) => {
+                    for (name, profile) in b {
+                        let merged = match a.remove(&name) {
+                            Some(base) => base.merge(profile),
+                            None => profile,
+                        };
+                        a.insert(name, merged);
+                    }
+                    Some(a)
+                }
+                (a, b) => b.or(a),
+            },
         }
     }
+
+    /// Layer the named profile over this config. Profiles with the same name
+    /// in the user and project files have already been merged.
+    fn with_profile(mut self, name: &str) -> Result<Config, String> {
+        let mut profiles = self.profiles.take().unwrap_or_default();
+        let available: Vec<String> = profiles.keys().cloned().collect();
+        let profile = profiles.remove(name).ok_or_else(|| {
+            if available.is_empty() {
+                format!("unknown config profile '{}': no profiles are defined", name)
+            } else {
+                format!(
+                    "unknown config profile '{}'. Available: {}",
+                    name,
+                    available.join(", ")
+                )
+            }
+        })?;
+        Ok(self.merge(Config {
+            profiles: None,
+            ..profile
+        }))
+    }
+}
+
+/// Remove `//` and `/* */` comments outside of strings, so config files can
+/// be annotated. Newlines are kept to preserve error line numbers.
+pub fn strip_json_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|&n| n != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        out.push(n);
+                    }
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` with environment variables. The
+/// default is used when the variable is unset or empty; `$${VAR}` gives a
+/// literal `${VAR}`. `at` names the setting for error messages.
+fn interpolate(s: &str, at: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let len = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("{}: unterminated ${{ in \"{}\"", at, s))?;
+        let expr = &rest[start + 2..start + len];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+        let value = env::var(name)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .or_else(|| default.map(String::from))
+            .ok_or_else(|| {
+                format!(
+                    "{}: environment variable {} is not set (use ${{{}:-default}} for a fallback)",
+                    at, name, name
+                )
+            })?;
+        out.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Load a `$file:<path>` reference, relative to `base_dir`. JSON files are
+/// parsed (and resolved in turn); anything else is used as text.
+fn include_file(spec: &str, base_dir: &Path, at: &str) -> Result<Value, String> {
+    let path = base_dir.join(interpolate(spec, at)?);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("{}: cannot read {}: {}", at, path.display(), e))?;
+    if path.extension().is_some_and(|e| e == "json") {
+        let mut value: Value = serde_json::from_str(&strip_json_comments(&content))
+            .map_err(|e| format!("{}: invalid JSON in {}: {}", at, path.display(), e))?;
+        let dir = path.parent().unwrap_or(base_dir);
+        resolve_value(&mut value, dir, at)?;
+        Ok(value)
+    } else {
+        Ok(Value::String(interpolate(content.trim_end(), at)?))
+    }
+}
+
+fn resolve_value(value: &mut Value, base_dir: &Path, at: &str) -> Result<(), String> {
+    match value {
+        Value::String(s) => {
+            *value = match s.strip_prefix("$file:") {
+                Some(spec) => include_file(spec, base_dir, at)?,
+                None => Value::String(interpolate(s, at)?),
+            };
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                resolve_value(item, base_dir, &format!("{}[{}]", at, i))?;
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                resolve_value(item, base_dir, &format!("{}.{}", at, key))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Resolve `${VAR}` and `$file:` references in the values of a parsed config
+/// file (and its profiles). `base_dir` is the file's directory; `extends` is
+/// left for the caller.
+pub fn resolve_config_value(value: &mut Value, base_dir: &Path) -> Result<(), String> {
+    let Value::Object(map) = value else {
+        return Ok(());
+    };
+    resolve_object(map, base_dir, "")
+}
+
+fn resolve_object(
+    map: &mut Map<String, Value>,
+    base_dir: &Path,
+    prefix: &str,
+) -> Result<(), String> {
+    for (key, value) in map.iter_mut() {
+        let at = format!("{}{}", prefix, key);
+        match key.as_str() {
+            // ${name} in aliases refers to variables saved with --save-as
+            "aliases" | "extends" => continue,
+            "profiles" => {
+                if let Value::Object(profiles) = value {
+                    for (name, profile) in profiles.iter_mut() {
+                        if let Value::Object(profile) = profile {
+                            let nested = format!("{}profiles.{}.", prefix, name);
+                            resolve_object(profile, base_dir, &nested)?;
+                        }
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+        let included = value.as_str().is_some_and(|s| s.starts_with("$file:"));
+        resolve_value(value, base_dir, &at)?;
+        // A JSON file included for a string setting (like headers) is used as text
+        if included && !value.is_string() {
+            let single = |v: Value| Value::Object(Map::from_iter([(key.clone(), v)]));
+            if serde_json::from_value::<Config>(single(value.clone())).is_err() {
+                *value = Value::String(value.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read a config file. Missing files are `Ok(None)`; invalid JSON or values
+/// are warned about and skipped. References that can't be resolved (unset
+/// variables, missing `$file:` or `extends` targets) are errors.
+pub fn read_config_file(path: &Path) -> Result<Option<Config>, String> {
+    read_config_chain(path, &mut Vec::new())
 }
 
-fn read_config_file(path: &Path) -> Option<Config> {
-    let content = fs::read_to_string(path).ok()?;
-    match serde_json::from_str::<Config>(&content) {
-        Ok(config) => Some(config),
+/// `chain` holds the files already being read, to catch `extends` cycles.
+fn read_config_chain(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Option<Config>, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let warn = |e: &dyn std::fmt::Display| {
+        eprintln!(
+            "{} invalid config file {}: {}",
+            color::warning_indicator(),
+            path.display(),
+            e
+        );
+    };
+    let mut value: Value = match serde_json::from_str(&strip_json_comments(&content)) {
+        Ok(v) => v,
+        Err(e) => {
+            warn(&e);
+            return Ok(None);
+        }
+    };
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let extends = value.as_object_mut().and_then(|m| m.remove("extends"));
+    resolve_config_value(&mut value, base_dir).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let config = match serde_json::from_value::<Config>(value) {
+        Ok(config) => config,
         Err(e) => {
-            eprintln!(
-                "{} invalid config file {}: {}",
-                color::warning_indicator(),
-                path.display(),
-                e
-            );
-            None
+            warn(&e);
+            return Ok(None);
         }
+    };
+
+    let Some(extends) = extends else {
+        return Ok(Some(config));
+    };
+    let Value::String(base) = extends else {
+        return Err(format!("{}: \"extends\" must be a file path", path.display()));
+    };
+    let base = interpolate(&base, "extends").map_err(|e| format!("{}: {}", path.display(), e))?;
+    let base_path = base_dir.join(base);
+    let canonical = base_path.canonicalize().map_err(|_| {
+        format!(
+            "{}: extends {}, which does not exist",
+            path.display(),
+            base_path.display()
+        )
+    })?;
+    if chain.is_empty() {
+        chain.push(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+    }
+    if chain.contains(&canonical) {
+        return Err(format!(
+            "{}: extends {}, which extends it back",
+            path.display(),
+            base_path.display()
+        ));
     }
+    chain.push(canonical);
+    let base = read_config_chain(&base_path, chain)?.ok_or_else(|| {
+        format!("{}: cannot extend invalid config {}", path.display(), base_path.display())
+    })?;
+    chain.pop();
+    Ok(Some(base.merge(config)))
 }
 
 /// Check if a boolean environment variable is set to a truthy value.
@@ -125,44 +503,36 @@ fn parse_bool_arg(args: &[String], i: usize) -> (bool, bool) {
     }
 }
 
+/// Split a comma-separated flag value into trimmed, lowercase, non-empty entries.
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Whether `arg` is a global flag followed by a value.
+fn takes_value(arg: &str) -> bool {
+    registry::global_option(arg).is_some_and(|o| o.value.is_some())
+}
+
 /// Extract --config <path> from args before full flag parsing.
 /// Returns `Some(Some(path))` if --config <path> found, `Some(None)` if --config
 /// was the last arg with no value, `None` if --config not present.
 ///
-/// Only flags that consume a following argument need to be listed here.
-/// Boolean flags (--content-boundaries, --confirm-interactive, etc.) are
-/// intentionally absent -- they don't take a value, so they can't cause
-/// the next argument to be mis-consumed.
+/// Flags that consume a following argument are skipped so their value
+/// can't be mistaken for --config.
 fn extract_config_path(args: &[String]) -> Option<Option<String>> {
-    const FLAGS_WITH_VALUE: &[&str] = &[
-        "--session",
-        "--headers",
-        "--executable-path",
-        "--cdp",
-        "--extension",
-        "--profile",
-        "--state",
-        "--proxy",
-        "--proxy-bypass",
-        "--args",
-        "--user-agent",
-        "-p",
-        "--provider",
-        "--device",
-        "--session-name",
-        "--color-scheme",
-        "--download-path",
-        "--max-output",
-        "--allowed-domains",
-        "--action-policy",
-        "--confirm-actions",
-    ];
+    extract_flag_value(args, "--config")
+}
+
+fn extract_flag_value(args: &[String], flag: &str) -> Option<Option<String>> {
     let mut i = 0;
     while i < args.len() {
-        if args[i] == "--config" {
+        if args[i] == flag {
             return Some(args.get(i + 1).cloned());
         }
-        if FLAGS_WITH_VALUE.contains(&args[i].as_str()) {
+        if takes_value(&args[i]) {
             i += 1;
         }
         i += 1;
@@ -170,7 +540,30 @@ fn extract_config_path(args: &[String]) -> Option<Option<String>> {
     None
 }
 
-pub fn load_config(args: &[String]) -> Result<Config, String> {
+/// `~/.agent-browser/config.json`, the user-level config file.
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|d| d.join(CONFIG_DIR).join(CONFIG_FILENAME))
+}
+
+/// A config file consulted by `load_config`.
+pub struct ConfigSource {
+    /// Where the path came from: `--config`, `AGENT_BROWSER_CONFIG`, `user`
+    /// or `project`.
+    pub origin: &'static str,
+    pub path: PathBuf,
+}
+
+impl ConfigSource {
+    /// An explicit config file replaces the user and project files.
+    pub fn is_explicit(&self) -> bool {
+        !matches!(self.origin, "user" | "project")
+    }
+}
+
+/// The config files `load_config` reads, lowest priority first: the explicit
+/// `--config`/`AGENT_BROWSER_CONFIG` file alone, or the user file followed by
+/// the project file.
+pub fn config_sources(args: &[String]) -> Result<Vec<ConfigSource>, String> {
     let explicit = extract_config_path(args)
         .map(|p| ("--config", p))
         .or_else(|| {
@@ -179,28 +572,61 @@ pub fn load_config(args: &[String]) -> Result<Config, String> {
                 .map(|p| ("AGENT_BROWSER_CONFIG", Some(p)))
         });
 
-    if let Some((source, maybe_path)) = explicit {
-        let path_str =
-            maybe_path.ok_or_else(|| format!("{} requires a file path", source))?;
-        let path = PathBuf::from(&path_str);
-        if !path.exists() {
-            return Err(format!("config file not found: {}", path_str));
-        }
-        return read_config_file(&path)
-            .ok_or_else(|| format!("failed to load config from {}", path_str));
+    if let Some((origin, maybe_path)) = explicit {
+        let path = maybe_path.ok_or_else(|| format!("{} requires a file path", origin))?;
+        return Ok(vec![ConfigSource {
+            origin,
+            path: PathBuf::from(path),
+        }]);
     }
 
-    let user_config = dirs::home_dir()
-        .map(|d| d.join(CONFIG_DIR).join(CONFIG_FILENAME))
-        .and_then(|p| read_config_file(&p))
-        .unwrap_or_default();
+    let mut sources = Vec::new();
+    if let Some(path) = user_config_path() {
+        sources.push(ConfigSource {
+            origin: "user",
+            path,
+        });
+    }
+    sources.push(ConfigSource {
+        origin: "project",
+        path: PathBuf::from(PROJECT_CONFIG_FILENAME),
+    });
+    Ok(sources)
+}
+
+/// The config profile selected with `--env <name>` or `AGENT_BROWSER_ENV`.
+pub fn selected_profile(args: &[String]) -> Result<Option<String>, String> {
+    match extract_flag_value(args, "--env") {
+        Some(Some(name)) => Ok(Some(name)),
+        Some(None) => Err("--env requires a profile name".to_string()),
+        None => Ok(env::var("AGENT_BROWSER_ENV").ok().filter(|v| !v.is_empty())),
+    }
+}
+
+pub fn load_config(args: &[String]) -> Result<Config, String> {
+    let sources = config_sources(args)?;
 
-    let project_config = read_config_file(&PathBuf::from(PROJECT_CONFIG_FILENAME));
+    let config = if let Some(source) = sources.iter().find(|s| s.is_explicit()) {
+        let path = &source.path;
+        if !path.exists() {
+            return Err(format!("config file not found: {}", path.display()));
+        }
+        read_config_file(path)?
+            .ok_or_else(|| format!("failed to load config from {}", path.display()))?
+    } else {
+        let mut config = Config::default();
+        for source in &sources {
+            if let Some(file) = read_config_file(&source.path)? {
+                config = config.merge(file);
+            }
+        }
+        config
+    };
 
-    Ok(match project_config {
-        Some(project) => user_config.merge(project),
-        None => user_config,
-    })
+    match selected_profile(args)? {
+        Some(name) => config.with_profile(&name),
+        None => Ok(config),
+    }
 }
 
 pub struct Flags {
@@ -217,6 +643,10 @@ pub struct Flags {
     pub state: Option<String>,
     pub proxy: Option<String>,
     pub proxy_bypass: Option<String>,
+    pub proxy_pac: Option<String>,
+    pub proxy_password_env: Option<String>,
+    pub proxy_list: Option<String>,
+    pub proxy_rotate: bool,
     pub args: Option<String>,
     pub user_agent: Option<String>,
     pub provider: Option<String>,
@@ -229,11 +659,31 @@ pub struct Flags {
     pub color_scheme: Option<String>,
     pub download_path: Option<String>,
     pub content_boundaries: bool,
+    pub injection_scan: Option<String>,
     pub max_output: Option<usize>,
+    pub max_tokens: Option<usize>,
+    pub offset: Option<usize>,
+    pub page: Option<usize>,
+    pub default_timeout: Option<u64>,
+    pub navigation_timeout: Option<u64>,
+    pub action_timeout: Option<u64>,
+    pub wait_timeout: Option<u64>,
     pub allowed_domains: Option<Vec<String>>,
+    pub block: Option<Vec<String>>,
+    pub ca_certs: Vec<String>,
+    pub resolve: Vec<String>,
+    pub client_certs: Vec<ClientCert>,
+    /// The first `--client-cert` value that failed to parse, reported by main
+    pub client_cert_error: Option<String>,
     pub action_policy: Option<String>,
     pub confirm_actions: Option<String>,
     pub confirm_interactive: bool,
+    pub report: Option<String>,
+    pub aliases: BTreeMap<String, Alias>,
+    pub dry_run: bool,
+    pub field: Option<String>,
+    pub format: Option<String>,
+    pub out: Option<String>,
 
     // Track which launch-time options were explicitly passed via CLI
     // (as opposed to being set only via environment variables)
@@ -245,15 +695,20 @@ pub struct Flags {
     pub cli_user_agent: bool,
     pub cli_proxy: bool,
     pub cli_proxy_bypass: bool,
+    pub cli_proxy_pac: bool,
     pub cli_allow_file_access: bool,
     pub cli_annotate: bool,
     pub cli_download_path: bool,
+    pub cli_ca_certs: bool,
+    pub cli_resolve: bool,
+    pub cli_client_certs: bool,
+    pub cli_default_timeout: bool,
 }
 
 pub fn parse_flags(args: &[String]) -> Flags {
     let config = load_config(args).unwrap_or_else(|e| {
         eprintln!("{} {}", color::warning_indicator(), e);
-        std::process::exit(1);
+        std::process::exit(exit_code::USAGE);
     });
 
     let extensions_env = env::var("AGENT_BROWSER_EXTENSIONS")
@@ -272,6 +727,14 @@ pub fn parse_flags(args: &[String]) -> Flags {
         config.extensions.unwrap_or_default()
     };
 
+    let (env_client_certs, client_cert_error) = match env::var("AGENT_BROWSER_CLIENT_CERTS") {
+        Ok(s) => match ClientCert::parse_list(&s) {
+            Ok(certs) => (Some(certs), None),
+            Err(e) => (None, Some(e)),
+        },
+        Err(_) => (None, None),
+    };
+
     let mut flags = Flags {
         json: env_var_is_truthy("AGENT_BROWSER_JSON")
             || config.json.unwrap_or(false),
@@ -297,6 +760,14 @@ pub fn parse_flags(args: &[String]) -> Flags {
             .or(config.proxy),
         proxy_bypass: env::var("AGENT_BROWSER_PROXY_BYPASS").ok()
             .or(config.proxy_bypass),
+        proxy_pac: env::var("AGENT_BROWSER_PROXY_PAC").ok()
+            .or(config.proxy_pac),
+        proxy_password_env: env::var("AGENT_BROWSER_PROXY_PASSWORD_ENV").ok()
+            .or(config.proxy_password_env),
+        proxy_list: env::var("AGENT_BROWSER_PROXY_LIST").ok()
+            .or(config.proxy_list),
+        proxy_rotate: env_var_is_truthy("AGENT_BROWSER_PROXY_ROTATE")
+            || config.proxy_rotate.unwrap_or(false),
         args: env::var("AGENT_BROWSER_ARGS").ok()
             .or(config.args),
         user_agent: env::var("AGENT_BROWSER_USER_AGENT").ok()
@@ -321,18 +792,56 @@ pub fn parse_flags(args: &[String]) -> Flags {
             .or(config.download_path),
         content_boundaries: env_var_is_truthy("AGENT_BROWSER_CONTENT_BOUNDARIES")
             || config.content_boundaries.unwrap_or(false),
+        injection_scan: env::var("AGENT_BROWSER_INJECTION_SCAN").ok()
+            .or(config.injection_scan),
         max_output: env::var("AGENT_BROWSER_MAX_OUTPUT").ok()
             .and_then(|s| s.parse().ok())
             .or(config.max_output),
+        max_tokens: env::var("AGENT_BROWSER_MAX_TOKENS").ok()
+            .and_then(|s| s.parse().ok())
+            .or(config.max_tokens),
+        offset: None,
+        page: None,
+        default_timeout: env::var("AGENT_BROWSER_DEFAULT_TIMEOUT").ok()
+            .and_then(|s| s.parse().ok())
+            .or(config.default_timeout),
+        navigation_timeout: env::var("AGENT_BROWSER_NAVIGATION_TIMEOUT").ok()
+            .and_then(|s| s.parse().ok())
+            .or(config.navigation_timeout),
+        action_timeout: env::var("AGENT_BROWSER_ACTION_TIMEOUT").ok()
+            .and_then(|s| s.parse().ok())
+            .or(config.action_timeout),
+        wait_timeout: env::var("AGENT_BROWSER_WAIT_TIMEOUT").ok()
+            .and_then(|s| s.parse().ok())
+            .or(config.wait_timeout),
         allowed_domains: env::var("AGENT_BROWSER_ALLOWED_DOMAINS").ok()
             .map(|s| s.split(',').map(|d| d.trim().to_lowercase()).filter(|d| !d.is_empty()).collect())
             .or(config.allowed_domains),
+        block: env::var("AGENT_BROWSER_BLOCK").ok()
+            .map(|s| split_list(&s))
+            .or(config.block),
+        ca_certs: env::var("AGENT_BROWSER_CA_CERTS").ok()
+            .map(|s| s.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect())
+            .or(config.ca_certs)
+            .unwrap_or_default(),
+        resolve: env::var("AGENT_BROWSER_RESOLVE").ok()
+            .map(|s| s.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect())
+            .or(config.resolve)
+            .unwrap_or_default(),
+        client_certs: env_client_certs.or(config.client_certs).unwrap_or_default(),
+        client_cert_error,
         action_policy: env::var("AGENT_BROWSER_ACTION_POLICY").ok()
             .or(config.action_policy),
         confirm_actions: env::var("AGENT_BROWSER_CONFIRM_ACTIONS").ok()
             .or(config.confirm_actions),
         confirm_interactive: env_var_is_truthy("AGENT_BROWSER_CONFIRM_INTERACTIVE")
             || config.confirm_interactive.unwrap_or(false),
+        report: env::var("AGENT_BROWSER_REPORT").ok().or(config.report),
+        aliases: config.aliases.unwrap_or_default(),
+        dry_run: env_var_is_truthy("AGENT_BROWSER_DRY_RUN"),
+        field: None,
+        format: None,
+        out: None,
         cli_executable_path: false,
         cli_extensions: false,
         cli_profile: false,
@@ -341,9 +850,14 @@ pub fn parse_flags(args: &[String]) -> Flags {
         cli_user_agent: false,
         cli_proxy: false,
         cli_proxy_bypass: false,
+        cli_proxy_pac: false,
         cli_allow_file_access: false,
         cli_annotate: false,
         cli_download_path: false,
+        cli_ca_certs: false,
+        cli_resolve: false,
+        cli_client_certs: false,
+        cli_default_timeout: false,
     };
 
     let mut i = 0;
@@ -429,6 +943,30 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--proxy-pac" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.proxy_pac = Some(s.clone());
+                    flags.cli_proxy_pac = true;
+                    i += 1;
+                }
+            }
+            "--proxy-password-env" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.proxy_password_env = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--proxy-list" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.proxy_list = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--proxy-rotate" => {
+                let (val, consumed) = parse_bool_arg(args, i);
+                flags.proxy_rotate = val;
+                if consumed { i += 1; }
+            }
             "--args" => {
                 if let Some(s) = args.get(i + 1) {
                     flags.args = Some(s.clone());
@@ -501,6 +1039,12 @@ pub fn parse_flags(args: &[String]) -> Flags {
                 flags.content_boundaries = val;
                 if consumed { i += 1; }
             }
+            "--injection-scan" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.injection_scan = Some(s.clone());
+                    i += 1;
+                }
+            }
             "--max-output" => {
                 if let Some(s) = args.get(i + 1) {
                     if let Ok(n) = s.parse::<usize>() {
@@ -509,6 +1053,39 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--max-tokens" => {
+                if let Some(s) = args.get(i + 1) {
+                    if let Ok(n) = s.parse::<usize>() {
+                        flags.max_tokens = Some(n);
+                    }
+                    i += 1;
+                }
+            }
+            "--offset" => {
+                if let Some(s) = args.get(i + 1) {
+                    if let Ok(n) = s.parse::<usize>() {
+                        flags.offset = Some(n);
+                    }
+                    i += 1;
+                }
+            }
+            "--page" => {
+                if let Some(s) = args.get(i + 1) {
+                    if let Ok(n) = s.parse::<usize>() {
+                        flags.page = Some(n.max(1));
+                    }
+                    i += 1;
+                }
+            }
+            "--default-timeout" => {
+                if let Some(s) = args.get(i + 1) {
+                    if let Ok(n) = s.parse::<u64>() {
+                        flags.default_timeout = Some(n);
+                        flags.cli_default_timeout = true;
+                    }
+                    i += 1;
+                }
+            }
             "--allowed-domains" => {
                 if let Some(s) = args.get(i + 1) {
                     flags.allowed_domains = Some(
@@ -517,6 +1094,38 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--block" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.block = Some(split_list(s));
+                    i += 1;
+                }
+            }
+            "--ca-cert" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.ca_certs.push(s.clone());
+                    flags.cli_ca_certs = true;
+                    i += 1;
+                }
+            }
+            "--resolve" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.resolve.push(s.clone());
+                    flags.cli_resolve = true;
+                    i += 1;
+                }
+            }
+            "--client-cert" => {
+                if let Some(s) = args.get(i + 1) {
+                    match ClientCert::parse(s) {
+                        Ok(cert) => flags.client_certs.push(cert),
+                        Err(e) => {
+                            flags.client_cert_error.get_or_insert(e);
+                        }
+                    }
+                    flags.cli_client_certs = true;
+                    i += 1;
+                }
+            }
             "--action-policy" => {
                 if let Some(s) = args.get(i + 1) {
                     flags.action_policy = Some(s.clone());
@@ -529,12 +1138,41 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--report" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.report = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--field" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.field = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--format" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.format = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--out" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.out = Some(s.clone());
+                    i += 1;
+                }
+            }
             "--confirm-interactive" => {
                 let (val, consumed) = parse_bool_arg(args, i);
                 flags.confirm_interactive = val;
                 if consumed { i += 1; }
             }
-            "--config" => {
+            "--dry-run" => {
+                let (val, consumed) = parse_bool_arg(args, i);
+                flags.dry_run = val;
+                if consumed { i += 1; }
+            }
+            "--config" | "--env" => {
                 // Already handled by load_config(); skip the value
                 i += 1;
             }
@@ -549,45 +1187,6 @@ pub fn clean_args(args: &[String]) -> Vec<String> {
     let mut result = Vec::new();
     let mut skip_next = false;
 
-    // Boolean flags that optionally take true/false
-    const GLOBAL_BOOL_FLAGS: &[&str] = &[
-        "--json",
-        "--full",
-        "--headed",
-        "--debug",
-        "--ignore-https-errors",
-        "--allow-file-access",
-        "--auto-connect",
-        "--annotate",
-        "--content-boundaries",
-        "--confirm-interactive",
-    ];
-    // Global flags that always take a value (need to skip the next arg too)
-    const GLOBAL_FLAGS_WITH_VALUE: &[&str] = &[
-        "--session",
-        "--headers",
-        "--executable-path",
-        "--cdp",
-        "--extension",
-        "--profile",
-        "--state",
-        "--proxy",
-        "--proxy-bypass",
-        "--args",
-        "--user-agent",
-        "-p",
-        "--provider",
-        "--device",
-        "--session-name",
-        "--color-scheme",
-        "--download-path",
-        "--max-output",
-        "--allowed-domains",
-        "--action-policy",
-        "--confirm-actions",
-        "--config",
-    ];
-
     let mut i = 0;
     while i < args.len() {
         let arg = &args[i];
@@ -596,12 +1195,13 @@ pub fn clean_args(args: &[String]) -> Vec<String> {
             i += 1;
             continue;
         }
-        if GLOBAL_FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+        if takes_value(arg) {
             skip_next = true;
             i += 1;
             continue;
         }
-        if GLOBAL_BOOL_FLAGS.contains(&arg.as_str()) || arg == "-f" {
+        // Boolean flags optionally take true/false
+        if registry::global_option(arg).is_some() {
             if let Some(v) = args.get(i + 1) {
                 if matches!(v.as_str(), "true" | "false") {
                     i += 1;
@@ -919,7 +1519,7 @@ mod tests {
         let mut f = fs::File::create(&config_path).unwrap();
         writeln!(f, r#"{{"headed": true, "proxy": "http://test:1234"}}"#).unwrap();
 
-        let config = read_config_file(&config_path).unwrap();
+        let config = read_config_file(&config_path).unwrap().unwrap();
         assert_eq!(config.headed, Some(true));
         assert_eq!(config.proxy.as_deref(), Some("http://test:1234"));
 
@@ -930,7 +1530,7 @@ mod tests {
     #[test]
     fn test_load_config_missing_file_returns_none() {
         let result = read_config_file(&PathBuf::from("/nonexistent/agent-browser.json"));
-        assert!(result.is_none());
+        assert!(matches!(result, Ok(None)));
     }
 
     #[test]
@@ -943,7 +1543,7 @@ mod tests {
         writeln!(f, "{{not valid json}}").unwrap();
 
         let result = read_config_file(&config_path);
-        assert!(result.is_none());
+        assert!(matches!(result, Ok(None)));
 
         let _ = fs::remove_file(&config_path);
         let _ = fs::remove_dir(&dir);
@@ -1001,6 +1601,138 @@ mod tests {
         let _ = fs::remove_dir(&dir);
     }
 
+    #[test]
+    fn test_load_config_with_profile() {
+        let dir = std::env::temp_dir().join("ab-test-profile-config");
+        let _ = fs::create_dir_all(&dir);
+        let config_path = dir.join("profiles.json");
+        fs::write(
+            &config_path,
+            r#"{
+                "proxy": "http://base:8080",
+                "headed": true,
+                "profiles": {
+                    "ci": { "headed": false, "allowedDomains": ["ci.example.com"] },
+                    "staging": { "proxy": "http://staging:8080" }
+                }
+            }"#,
+        )
+        .unwrap();
+        let path = config_path.to_string_lossy().to_string();
+
+        let config = load_config(&args(&format!("--config {} --env ci open", path))).unwrap();
+        assert_eq!(config.headed, Some(false));
+        assert_eq!(config.proxy.as_deref(), Some("http://base:8080"));
+        assert_eq!(config.allowed_domains, Some(vec!["ci.example.com".to_string()]));
+
+        let config = load_config(&args(&format!("--config {}", path))).unwrap();
+        assert_eq!(config.headed, Some(true));
+
+        let err = load_config(&args(&format!("--config {} --env prod", path))).unwrap_err();
+        assert_eq!(err, "unknown config profile 'prod'. Available: ci, staging");
+        assert!(load_config(&args(&format!("--config {} --env", path))).is_err());
+
+        let _ = fs::remove_file(&config_path);
+        let _ = fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn test_merge_profiles_by_name() {
+        let user: Config = serde_json::from_str(
+            r#"{ "profiles": { "ci": { "headed": true, "proxy": "http://u" }, "dev": {} } }"#,
+        )
+        .unwrap();
+        let project: Config =
+            serde_json::from_str(r#"{ "profiles": { "ci": { "proxy": "http://p" } } }"#).unwrap();
+        let merged = user.merge(project).with_profile("ci").unwrap();
+        assert_eq!(merged.headed, Some(true));
+        assert_eq!(merged.proxy.as_deref(), Some("http://p"));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        env::set_var("AB_TEST_INTERP", "value");
+        assert_eq!(interpolate("a-${AB_TEST_INTERP}-b", "k").unwrap(), "a-value-b");
+        assert_eq!(interpolate("${AB_TEST_INTERP_UNSET:-dflt}", "k").unwrap(), "dflt");
+        assert_eq!(
+            interpolate("$${AB_TEST_INTERP}", "k").unwrap(),
+            "${AB_TEST_INTERP}"
+        );
+        assert_eq!(
+            interpolate("${AB_TEST_INTERP_UNSET}", "proxy").unwrap_err(),
+            "proxy: environment variable AB_TEST_INTERP_UNSET is not set (use ${AB_TEST_INTERP_UNSET:-default} for a fallback)"
+        );
+        assert!(interpolate("${AB_TEST_INTERP", "k").is_err());
+    }
+
+    #[test]
+    fn test_config_extends_and_file_references() {
+        let dir = std::env::temp_dir().join("ab-test-config-includes");
+        let _ = fs::create_dir_all(dir.join("shared"));
+        fs::write(
+            dir.join("shared/base.json"),
+            r#"{ "headed": true, "extensions": ["./a"], "proxy": "http://base" }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("headers.json"),
+            r#"{ "Authorization": "Bearer ${AB_TEST_CFG_TOKEN}" }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("domains.json"), r#"["a.com", "b.com"]"#).unwrap();
+        fs::write(
+            dir.join("main.json"),
+            r#"{
+                "extends": "./shared/base.json",
+                "extensions": ["./b"],
+                "headers": "$file:./headers.json",
+                "allowedDomains": "$file:domains.json",
+                "proxy": "${AB_TEST_CFG_PROXY:-http://fallback}",
+                "aliases": { "back": "open ${start}" }
+            }"#,
+        )
+        .unwrap();
+        env::set_var("AB_TEST_CFG_TOKEN", "s3cret");
+
+        let config = read_config_file(&dir.join("main.json")).unwrap().unwrap();
+        assert_eq!(config.headed, Some(true));
+        assert_eq!(
+            config.extensions,
+            Some(vec!["./a".to_string(), "./b".to_string()])
+        );
+        assert_eq!(
+            config.headers.as_deref(),
+            Some(r#"{"Authorization":"Bearer s3cret"}"#)
+        );
+        assert_eq!(
+            config.allowed_domains,
+            Some(vec!["a.com".to_string(), "b.com".to_string()])
+        );
+        assert_eq!(config.proxy.as_deref(), Some("http://fallback"));
+        assert_eq!(
+            config.aliases.unwrap()["back"],
+            Alias::Command("open ${start}".to_string())
+        );
+
+        fs::write(dir.join("missing.json"), r#"{ "proxy": "${AB_TEST_CFG_UNSET}" }"#).unwrap();
+        let err = read_config_file(&dir.join("missing.json")).unwrap_err();
+        assert!(err.contains(
+            "missing.json: proxy: environment variable AB_TEST_CFG_UNSET is not set"
+        ));
+
+        fs::write(dir.join("a.json"), r#"{ "extends": "b.json" }"#).unwrap();
+        fs::write(dir.join("b.json"), r#"{ "extends": "a.json" }"#).unwrap();
+        let err = read_config_file(&dir.join("a.json")).unwrap_err();
+        assert!(err.contains("extends it back"), "{}", err);
+
+        fs::write(dir.join("c.json"), r#"{ "extends": "nope.json" }"#).unwrap();
+        assert!(read_config_file(&dir.join("c.json"))
+            .unwrap_err()
+            .contains("does not exist"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_load_config_error_missing_config_value() {
         let result = load_config(&args("--config"));
@@ -1148,6 +1880,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_config_merge_aliases_project_overrides_user() {
+        let user: Config = serde_json::from_str(
+            r#"{"aliases": {"home": "open example.com", "reset": ["cookies clear", "reload"]}}"#,
+        )
+        .unwrap();
+        let project: Config =
+            serde_json::from_str(r#"{"aliases": {"home": "open localhost:3000"}}"#).unwrap();
+        let aliases = user.merge(project).aliases.unwrap();
+        assert_eq!(aliases["home"], Alias::Command("open localhost:3000".to_string()));
+        assert_eq!(aliases["reset"].steps(), vec!["cookies clear", "reload"]);
+    }
+
     #[test]
     fn test_config_merge_extensions_user_only() {
         let user = Config {
@@ -1169,4 +1914,163 @@ mod tests {
         let merged = user.merge(project);
         assert_eq!(merged.extensions, Some(vec!["/ext2".to_string()]));
     }
+
+    #[test]
+    fn test_parse_block_flag() {
+        let flags = parse_flags(&args("open example.com --block Images,fonts,,media"));
+        assert_eq!(
+            flags.block,
+            Some(vec!["images".to_string(), "fonts".to_string(), "media".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_clean_args_removes_block() {
+        let clean = clean_args(&args("--block images open example.com"));
+        assert_eq!(clean, vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_config_deserialize_block() {
+        let config: Config = serde_json::from_str(r#"{"block": ["images", "fonts"]}"#).unwrap();
+        assert_eq!(
+            config.block,
+            Some(vec!["images".to_string(), "fonts".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_ca_cert_and_resolve_repeatable() {
+        let flags = parse_flags(&args(
+            "--ca-cert ./a.pem --ca-cert ./b.pem --resolve a.test:443:10.0.0.1 open https://a.test",
+        ));
+        assert_eq!(flags.ca_certs, vec!["./a.pem", "./b.pem"]);
+        assert_eq!(flags.resolve, vec!["a.test:443:10.0.0.1"]);
+        assert!(flags.cli_ca_certs);
+        assert!(flags.cli_resolve);
+
+        let clean = clean_args(&args(
+            "--ca-cert ./a.pem --resolve a.test:443:10.0.0.1 open https://a.test",
+        ));
+        assert_eq!(clean, vec!["open", "https://a.test"]);
+    }
+
+    #[test]
+    fn test_config_deserialize_ca_certs_and_resolve() {
+        let json = r#"{"caCerts": ["./ca.pem"], "resolve": ["a.test:443:127.0.0.1"]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.ca_certs, Some(vec!["./ca.pem".to_string()]));
+        assert_eq!(config.resolve, Some(vec!["a.test:443:127.0.0.1".to_string()]));
+    }
+
+    #[test]
+    fn test_client_cert_parse() {
+        let cert = ClientCert::parse(
+            "origin=https://internal.example.com,cert=./client.pem,key=./client.key,passphrase-env=CERT_PASS",
+        )
+        .unwrap();
+        assert_eq!(cert.origin, "https://internal.example.com");
+        assert_eq!(cert.cert, "./client.pem");
+        assert_eq!(cert.key, "./client.key");
+        assert_eq!(cert.passphrase_env.as_deref(), Some("CERT_PASS"));
+    }
+
+    #[test]
+    fn test_client_cert_parse_missing_key() {
+        let err = ClientCert::parse("origin=https://a.test,cert=./c.pem").unwrap_err();
+        assert!(err.contains("origin, cert and key are required"));
+    }
+
+    #[test]
+    fn test_client_cert_parse_unknown_key() {
+        let err = ClientCert::parse("origin=https://a.test,cert=c,key=k,pfx=x").unwrap_err();
+        assert!(err.contains("Unknown --client-cert key 'pfx'"));
+    }
+
+    #[test]
+    fn test_parse_client_cert_flag() {
+        let flags = parse_flags(&args(
+            "--client-cert origin=https://a.test,cert=c.pem,key=k.pem open https://a.test",
+        ));
+        assert_eq!(flags.client_certs.len(), 1);
+        assert!(flags.cli_client_certs);
+        let clean = clean_args(&args(
+            "--client-cert origin=https://a.test,cert=c.pem,key=k.pem open https://a.test",
+        ));
+        assert_eq!(clean, vec!["open", "https://a.test"]);
+    }
+
+    #[test]
+    fn test_parse_client_cert_flag_invalid() {
+        let flags = parse_flags(&args("--client-cert cert=c.pem --json open https://a.test"));
+        assert!(flags.client_certs.is_empty());
+        assert!(flags.client_cert_error.is_some());
+        assert!(flags.json);
+    }
+
+    #[test]
+    fn test_client_cert_parse_list() {
+        let certs =
+            ClientCert::parse_list(r#"[{"origin":"https://a.test","cert":"c.pem","key":"k.pem"}]"#)
+                .unwrap();
+        assert_eq!(certs[0].origin, "https://a.test");
+        let err = ClientCert::parse_list("origin=https://a.test").unwrap_err();
+        assert!(err.starts_with("Invalid AGENT_BROWSER_CLIENT_CERTS:"), "{}", err);
+    }
+
+    #[test]
+    fn test_config_deserialize_client_certs() {
+        let json = r#"{"clientCerts": [{"origin": "https://a.test", "cert": "c.pem", "key": "k.pem", "passphraseEnv": "P"}]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let certs = config.client_certs.unwrap();
+        assert_eq!(certs[0].origin, "https://a.test");
+        assert_eq!(certs[0].passphrase_env.as_deref(), Some("P"));
+    }
+
+    #[test]
+    fn test_client_cert_serializes_for_daemon() {
+        let cert = ClientCert::parse("origin=https://a.test,cert=c.pem,key=k.pem").unwrap();
+        assert_eq!(
+            serde_json::to_string(&cert).unwrap(),
+            r#"{"origin":"https://a.test","cert":"c.pem","key":"k.pem"}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_proxy_enhancement_flags() {
+        let input = args(
+            "--proxy-pac ./corp.pac --proxy-password-env PROXY_PASS --proxy-list ./proxies.txt --proxy-rotate open example.com",
+        );
+        let flags = parse_flags(&input);
+        assert_eq!(flags.proxy_pac.as_deref(), Some("./corp.pac"));
+        assert_eq!(flags.proxy_password_env.as_deref(), Some("PROXY_PASS"));
+        assert_eq!(flags.proxy_list.as_deref(), Some("./proxies.txt"));
+        assert!(flags.proxy_rotate);
+        assert_eq!(clean_args(&input), vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_proxy_rotate_false() {
+        let flags = parse_flags(&args("--proxy-rotate false open example.com"));
+        assert!(!flags.proxy_rotate);
+    }
+
+    #[test]
+    fn test_parse_dry_run_flag() {
+        let input = args("--dry-run --headed open example.com");
+        let flags = parse_flags(&input);
+        assert!(flags.dry_run);
+        assert_eq!(clean_args(&input), vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_parse_report_flag() {
+        let input = args("--report junit:out.xml assert title --contains Home");
+        let flags = parse_flags(&input);
+        assert_eq!(flags.report.as_deref(), Some("junit:out.xml"));
+        assert_eq!(
+            clean_args(&input),
+            vec!["assert", "title", "--contains", "Home"]
+        );
+    }
 }
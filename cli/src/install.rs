@@ -1,4 +1,5 @@
 use crate::color;
+use crate::exit_code;
 use std::process::{exit, Command, Stdio};
 
 pub fn run_install(with_deps: bool) {
@@ -104,7 +105,7 @@ pub fn run_install(with_deps: bool) {
                     "{} No supported package manager found (apt-get, dnf, or yum)",
                     color::error_indicator()
                 );
-                exit(1);
+                exit(exit_code::ERROR);
             };
 
             let install_cmd = match pkg_mgr {
@@ -180,12 +181,12 @@ pub fn run_install(with_deps: bool) {
                 );
                 println!("  agent-browser install --with-deps");
             }
-            exit(1);
+            exit(exit_code::ERROR);
         }
         Err(e) => {
             eprintln!("{} Failed to run npx: {}", color::error_indicator(), e);
             eprintln!("Make sure Node.js is installed and npx is in your PATH");
-            exit(1);
+            exit(exit_code::ERROR);
         }
     }
 }
@@ -0,0 +1,194 @@
+use crate::color;
+use crate::connection::{daemon_address, get_socket_dir};
+use crate::exit_code;
+use crate::flags::Flags;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+const PREFIX: &str = "agent-browser-";
+
+#[cfg(windows)]
+const EXTENSIONS: &[&str] = &["exe", "cmd", "bat"];
+
+/// Directories searched for plugins, in order: PATH, then ~/.agent-browser/plugins.
+fn search_dirs() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = env::var_os("PATH")
+        .map(|p| env::split_paths(&p).collect())
+        .unwrap_or_default();
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".agent-browser").join("plugins"));
+    }
+    paths
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(windows)]
+    {
+        meta.is_file()
+    }
+}
+
+/// Whether `name` can be a plugin: not empty, not a path, and not one of the
+/// prebuilt platform binaries shipped in the npm package (`linux-x64`, ...).
+fn is_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && !["darwin-", "linux-", "win32-"]
+            .iter()
+            .any(|p| name.starts_with(p))
+}
+
+/// Plugin name for an executable file name, e.g. `agent-browser-deploy` -> `deploy`.
+fn plugin_name(file_name: &str) -> Option<String> {
+    #[cfg(windows)]
+    let file_name = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if EXTENSIONS.contains(&ext.to_lowercase().as_str()) => stem,
+        _ => return None,
+    };
+    let name = file_name.strip_prefix(PREFIX)?;
+    is_plugin_name(name).then(|| name.to_string())
+}
+
+fn find_in(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    if !is_plugin_name(name) {
+        return None;
+    }
+    for dir in dirs {
+        #[cfg(unix)]
+        let candidates = vec![dir.join(format!("{}{}", PREFIX, name))];
+        #[cfg(windows)]
+        let candidates: Vec<PathBuf> = EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}{}.{}", PREFIX, name, ext)))
+            .collect();
+        if let Some(path) = candidates.into_iter().find(|p| is_executable(p)) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn discover_in(dirs: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut plugins: Vec<(String, PathBuf)> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut found: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter_map(|e| {
+                let name = plugin_name(&e.file_name().to_string_lossy())?;
+                is_executable(&e.path()).then(|| (name, e.path()))
+            })
+            .collect();
+        found.sort();
+        for (name, path) in found {
+            // Earlier directories win, like command lookup on PATH
+            if !plugins.iter().any(|(n, _)| *n == name) {
+                plugins.push((name, path));
+            }
+        }
+    }
+    plugins.sort();
+    plugins
+}
+
+/// Find the executable for `agent-browser <name>`.
+pub fn find(name: &str) -> Option<PathBuf> {
+    find_in(&search_dirs(), name)
+}
+
+/// All plugins on PATH and in ~/.agent-browser/plugins, sorted by name.
+pub fn discover() -> Vec<(String, PathBuf)> {
+    discover_in(&search_dirs())
+}
+
+/// Run a plugin with the remaining arguments. The resolved session, daemon
+/// address and output mode are passed through the environment. Does not return.
+pub fn exec(path: &Path, args: &[String], flags: &Flags) -> ! {
+    let mut cmd = Command::new(path);
+    cmd.args(args)
+        .env("AGENT_BROWSER_SESSION", &flags.session)
+        .env("AGENT_BROWSER_SOCKET_DIR", get_socket_dir())
+        .env("AGENT_BROWSER_SOCKET", daemon_address(&flags.session))
+        .env("AGENT_BROWSER_JSON", if flags.json { "1" } else { "0" });
+    if let Ok(exe) = env::current_exe() {
+        cmd.env("AGENT_BROWSER_BIN", exe);
+    }
+
+    #[cfg(unix)]
+    let err = {
+        use std::os::unix::process::CommandExt;
+        cmd.exec()
+    };
+    #[cfg(windows)]
+    let err = match cmd.status() {
+        Ok(status) => exit(status.code().unwrap_or(1)),
+        Err(e) => e,
+    };
+
+    let msg = format!("Failed to run plugin {}: {}", path.display(), err);
+    if flags.json {
+        println!("{}", serde_json::json!({ "success": false, "error": msg }));
+    } else {
+        eprintln!("{} {}", color::error_indicator(), msg);
+    }
+    exit(exit_code::USAGE);
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_exec(dir: &Path, name: &str, mode: u32) {
+        let path = dir.join(name);
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(
+            plugin_name("agent-browser-deploy").as_deref(),
+            Some("deploy")
+        );
+        assert_eq!(plugin_name("agent-browser-linux-x64"), None);
+        assert_eq!(plugin_name("agent-browser-"), None);
+        assert_eq!(plugin_name("other-tool"), None);
+    }
+
+    #[test]
+    fn test_find_and_discover() {
+        let root = env::temp_dir().join(format!("agent-browser-plugins-{}", std::process::id()));
+        let (first, second) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        write_exec(&first, "agent-browser-deploy", 0o755);
+        write_exec(&first, "agent-browser-notes", 0o644);
+        write_exec(&second, "agent-browser-deploy", 0o755);
+        write_exec(&second, "agent-browser-seed", 0o755);
+        let dirs = vec![first.clone(), second.clone()];
+
+        assert_eq!(
+            find_in(&dirs, "deploy"),
+            Some(first.join("agent-browser-deploy"))
+        );
+        assert_eq!(find_in(&dirs, "notes"), None);
+        assert_eq!(find_in(&dirs, "missing"), None);
+
+        let names: Vec<String> = discover_in(&dirs).into_iter().map(|(n, _)| n).collect();
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(names, vec!["deploy", "seed"]);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::connection::get_socket_dir;
+use std::fs;
+use std::path::PathBuf;
+
+/// Decode `%XX` escapes in proxy credentials (e.g. `p%40ss` -> `p@ss`).
+/// Malformed escapes are kept verbatim.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Read a proxy list file: one proxy URL per line, blank lines and `#` comments ignored.
+pub fn read_proxy_list(path: &str) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read proxy list '{}': {}", path, e))?;
+    let proxies: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if proxies.is_empty() {
+        return Err(format!("Proxy list '{}' is empty", path));
+    }
+    Ok(proxies)
+}
+
+/// FNV-1a hash, used instead of `DefaultHasher` whose algorithm is
+/// unspecified (and version-dependent), so values are stable across builds.
+pub(crate) fn fnv1a(s: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in s.bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Pick a stable proxy for a session, so each session keeps the same exit IP
+/// across invocations.
+pub fn proxy_for_session<'a>(proxies: &'a [String], session: &str) -> &'a str {
+    &proxies[(fnv1a(session) % proxies.len() as u64) as usize]
+}
+
+fn rotation_path(session: &str) -> PathBuf {
+    get_socket_dir().join(format!("{}.proxy-index", session))
+}
+
+/// Index of the proxy the next rotation picks, without advancing the cursor.
+fn next_rotation_index(proxies: &[String], session: &str) -> usize {
+    fs::read_to_string(rotation_path(session))
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .map(|i| (i + 1) % proxies.len())
+        .unwrap_or(0)
+}
+
+/// The proxy `next_rotated_proxy` would return, for --dry-run.
+pub fn peek_rotated_proxy(proxies: &[String], session: &str) -> String {
+    proxies[next_rotation_index(proxies, session)].clone()
+}
+
+/// Advance the session's rotation cursor and return the next proxy (round-robin).
+/// The cursor is stored next to the session's socket so it survives CLI invocations.
+pub fn next_rotated_proxy(proxies: &[String], session: &str) -> String {
+    let next = next_rotation_index(proxies, session);
+    let _ = fs::create_dir_all(get_socket_dir());
+    let _ = fs::write(rotation_path(session), next.to_string());
+    proxies[next].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("p%40ss%3Aw0rd"), "p@ss:w0rd");
+        assert_eq!(percent_decode("user%2bname"), "user+name");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn test_percent_decode_malformed() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("a%4"), "a%4");
+    }
+
+    #[test]
+    fn test_read_proxy_list() {
+        let path = std::env::temp_dir().join("agent-browser-test-proxies.txt");
+        fs::write(
+            &path,
+            "# residential\nhttp://a.proxy:8080\n\n  http://b.proxy:8080  \n",
+        )
+        .unwrap();
+        let list = read_proxy_list(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(list, vec!["http://a.proxy:8080", "http://b.proxy:8080"]);
+    }
+
+    #[test]
+    fn test_read_proxy_list_missing() {
+        let err = read_proxy_list("/nonexistent/proxies.txt").unwrap_err();
+        assert!(err.contains("Cannot read proxy list"));
+    }
+
+    #[test]
+    fn test_proxy_for_session_is_stable() {
+        let proxies: Vec<String> = (0..5).map(|i| format!("http://p{}:80", i)).collect();
+        let first = proxy_for_session(&proxies, "scraper-1");
+        assert_eq!(first, proxy_for_session(&proxies, "scraper-1"));
+        assert!(proxies.iter().any(|p| p == first));
+    }
+}
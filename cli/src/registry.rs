@@ -0,0 +1,1008 @@
+//! Command registry: names, arguments, options and enum values for every
+//! command and global flag. `parse_command` takes its subcommand lists and
+//! value enums from here, `clean_args` its global flags, and `schema` exports
+//! it for agent tool definitions. Tests check it against the parser and the
+//! per-command help so the three can't drift apart.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+/// Where shell completion gets candidates for a value, besides `values`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Complete {
+    /// Let the shell complete file names
+    Files,
+    /// `@eN` refs from the last snapshot
+    Refs,
+    /// Running sessions
+    Sessions,
+    /// Saved auth profiles
+    AuthProfiles,
+    /// Saved state names
+    States,
+    /// Saved state file names (`<name>.json`)
+    StateFiles,
+}
+
+/// A positional argument.
+#[derive(Clone, Copy, Debug)]
+pub struct Arg {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: Kind,
+    pub required: bool,
+    /// Takes the rest of the arguments
+    pub variadic: bool,
+    pub values: &'static [&'static str],
+    pub complete: Complete,
+}
+
+/// A `--flag`, with or without a value.
+#[derive(Clone, Copy, Debug)]
+pub struct Opt {
+    pub name: &'static str,
+    pub short: Option<&'static str>,
+    /// Value placeholder, e.g. `<ms>`; `None` for boolean flags
+    pub value: Option<&'static str>,
+    pub kind: Kind,
+    pub values: &'static [&'static str],
+    pub description: &'static str,
+    /// May be given more than once
+    pub repeatable: bool,
+    pub complete: Complete,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Command {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub summary: &'static str,
+    /// Usage without the `agent-browser ` prefix
+    pub usage: &'static str,
+    pub args: &'static [Arg],
+    pub options: &'static [Opt],
+    pub subcommands: &'static [Command],
+}
+
+const fn cmd(name: &'static str, summary: &'static str, usage: &'static str) -> Command {
+    Command {
+        name,
+        aliases: &[],
+        summary,
+        usage,
+        args: &[],
+        options: &[],
+        subcommands: &[],
+    }
+}
+
+impl Command {
+    const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+    const fn args(mut self, args: &'static [Arg]) -> Self {
+        self.args = args;
+        self
+    }
+    const fn options(mut self, options: &'static [Opt]) -> Self {
+        self.options = options;
+        self
+    }
+    const fn subcommands(mut self, subcommands: &'static [Command]) -> Self {
+        self.subcommands = subcommands;
+        self
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+
+    /// Subcommand names and aliases, as listed in "Valid options" errors.
+    pub fn subcommand_names(&self) -> Vec<&'static str> {
+        self.subcommands
+            .iter()
+            .flat_map(|s| std::iter::once(s.name).chain(s.aliases.iter().copied()))
+            .collect()
+    }
+
+    pub fn find_option(&self, name: &str) -> Option<&'static Opt> {
+        self.options
+            .iter()
+            .find(|o| o.name == name || o.short == Some(name))
+    }
+}
+
+const fn arg(name: &'static str, description: &'static str) -> Arg {
+    Arg {
+        name,
+        description,
+        kind: Kind::String,
+        required: true,
+        variadic: false,
+        values: &[],
+        complete: Complete::Files,
+    }
+}
+
+impl Arg {
+    const fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+    const fn variadic(mut self) -> Self {
+        self.variadic = true;
+        self
+    }
+    const fn int(mut self) -> Self {
+        self.kind = Kind::Integer;
+        self
+    }
+    const fn number(mut self) -> Self {
+        self.kind = Kind::Number;
+        self
+    }
+    const fn values(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+    const fn complete(mut self, complete: Complete) -> Self {
+        self.complete = complete;
+        self
+    }
+}
+
+/// A boolean flag.
+const fn flag(name: &'static str, description: &'static str) -> Opt {
+    Opt {
+        name,
+        short: None,
+        value: None,
+        kind: Kind::Boolean,
+        values: &[],
+        description,
+        repeatable: false,
+        complete: Complete::Files,
+    }
+}
+
+/// A flag that takes a value.
+const fn opt(name: &'static str, value: &'static str, description: &'static str) -> Opt {
+    Opt {
+        name,
+        short: None,
+        value: Some(value),
+        kind: Kind::String,
+        values: &[],
+        description,
+        repeatable: false,
+        complete: Complete::Files,
+    }
+}
+
+impl Opt {
+    const fn short(mut self, short: &'static str) -> Self {
+        self.short = Some(short);
+        self
+    }
+    const fn int(mut self) -> Self {
+        self.kind = Kind::Integer;
+        self
+    }
+    const fn number(mut self) -> Self {
+        self.kind = Kind::Number;
+        self
+    }
+    const fn values(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+    const fn repeatable(mut self) -> Self {
+        self.repeatable = true;
+        self
+    }
+    const fn complete(mut self, complete: Complete) -> Self {
+        self.complete = complete;
+        self
+    }
+}
+
+// === Enums shared with parse_command ===
+
+pub const DIRECTIONS: &[&str] = &["up", "down", "left", "right"];
+pub const LOAD_STATES: &[&str] = &["load", "domcontentloaded", "networkidle"];
+pub const NETWORK_PROFILES: &[&str] = &["slow-3g", "fast-3g", "4g", "custom", "off"];
+pub const EXPORT_FORMATS: &[&str] = &["curl", "fetch", "httpie", "python-requests"];
+pub const MEDIA_FEATURES: &[&str] = &["dark", "light", "reduced-motion"];
+pub const SAME_SITE: &[&str] = &["Strict", "Lax", "None"];
+pub const MOUSE_BUTTONS: &[&str] = &["left", "right", "middle"];
+pub const COLOR_SCHEMES: &[&str] = &["dark", "light", "no-preference"];
+pub const INJECTION_SCAN_MODES: &[&str] = &["warn", "strip"];
+pub const STORAGE_OPERATIONS: &[&str] = &["get", "set", "clear"];
+pub const ON_OFF: &[&str] = &["on", "off"];
+pub const SCHEMA_FORMATS: &[&str] = &["json-schema", "openai-tools", "anthropic-tools"];
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+pub const TIMEOUT_KINDS: &[&str] = &["default", "navigation", "action", "wait"];
+
+// === Shared arguments and options ===
+
+const SELECTOR: Arg = arg(
+    "selector",
+    "CSS selector, text=..., xpath=... or @ref from snapshot",
+)
+.complete(Complete::Refs);
+const SAVE_AS: Opt = opt(
+    "--save-as",
+    "<name>",
+    "Save the result as ${name} for later commands",
+);
+const ASSERT_TIMEOUT: Opt = opt("--timeout", "<ms>", "How long to retry (default: 5000)").int();
+const TEXT_MATCHERS: &[Opt] = &[
+    opt(
+        "--contains",
+        "<text>",
+        "Passes if the text contains this (whitespace-normalized)",
+    ),
+    opt(
+        "--equals",
+        "<text>",
+        "Passes if the text equals this (whitespace-normalized)",
+    ),
+    opt(
+        "--matches",
+        "<regex>",
+        "Passes if the text matches a regex or /regex/flags",
+    ),
+    ASSERT_TIMEOUT,
+];
+const FIND_EXACT: Opt = flag("--exact", "Require an exact text match");
+const FIND_ACTION: Arg = arg("action", "Action on the element (default: click)")
+    .optional()
+    .values(&["click", "fill", "check", "hover", "text"]);
+const FIND_TEXT: Arg = arg("text", "Text for fill").optional().variadic();
+
+const SELECTOR_ONLY: &[Arg] = &[SELECTOR];
+const SELECTOR_SAVE_AS: &[Opt] = &[SAVE_AS];
+
+pub const COMMANDS: &[Command] = &[
+    // === Navigation ===
+    cmd("open", "Navigate to a URL (https:// is added if missing)", "open <url>")
+        .aliases(&["goto", "navigate"])
+        .args(&[arg("url", "URL to open")]),
+    cmd("back", "Go back", "back"),
+    cmd("forward", "Go forward", "forward"),
+    cmd("reload", "Reload the page", "reload"),
+    // === Core actions ===
+    cmd("click", "Click an element", "click <selector> [--new-tab]")
+        .args(SELECTOR_ONLY)
+        .options(&[flag("--new-tab", "Open the link in a new tab")]),
+    cmd("dblclick", "Double-click an element", "dblclick <selector>").args(SELECTOR_ONLY),
+    cmd("fill", "Clear an input and fill it", "fill <selector> <text>")
+        .args(&[SELECTOR, arg("text", "Text to fill").variadic()]),
+    cmd("type", "Type into an element", "type <selector> <text>")
+        .args(&[SELECTOR, arg("text", "Text to type").variadic()]),
+    cmd("hover", "Hover an element", "hover <selector>").args(SELECTOR_ONLY),
+    cmd("focus", "Focus an element", "focus <selector>").args(SELECTOR_ONLY),
+    cmd("check", "Check a checkbox", "check <selector>").args(SELECTOR_ONLY),
+    cmd("uncheck", "Uncheck a checkbox", "uncheck <selector>").args(SELECTOR_ONLY),
+    cmd("select", "Select dropdown options", "select <selector> <value...>")
+        .args(&[SELECTOR, arg("values", "Option values").variadic()]),
+    cmd("drag", "Drag and drop", "drag <source> <target>").args(&[
+        arg("source", "Element to drag").complete(Complete::Refs),
+        arg("target", "Element to drop onto").complete(Complete::Refs),
+    ]),
+    cmd("upload", "Upload files to a file input", "upload <selector> <files...>")
+        .args(&[SELECTOR, arg("files", "File paths").variadic()]),
+    cmd("download", "Download a file by clicking an element", "download <selector> <path>")
+        .args(&[SELECTOR, arg("path", "Where to save the file")]),
+    // === Keyboard ===
+    cmd("press", "Press a key or chord (Enter, Tab, Control+a)", "press <key>")
+        .aliases(&["key"])
+        .args(&[arg("key", "Key name or chord")]),
+    cmd("keydown", "Hold a key down", "keydown <key>").args(&[arg("key", "Key name")]),
+    cmd("keyup", "Release a key", "keyup <key>").args(&[arg("key", "Key name")]),
+    cmd("keyboard", "Keyboard input without a selector", "keyboard <type|inserttext> <text>")
+        .subcommands(&[
+            cmd("type", "Type text with real keystrokes", "keyboard type <text>")
+                .args(&[arg("text", "Text to type").variadic()]),
+            cmd("inserttext", "Insert text without key events", "keyboard inserttext <text>")
+                .aliases(&["insertText"])
+                .args(&[arg("text", "Text to insert").variadic()]),
+        ]),
+    // === Scroll ===
+    cmd("scroll", "Scroll the page or an element", "scroll [direction] [amount] [--selector <sel>]")
+        .args(&[
+            arg("direction", "Scroll direction (default: down)")
+                .optional()
+                .values(DIRECTIONS),
+            arg("amount", "Pixels to scroll (default: 300)").optional().int(),
+        ])
+        .options(&[opt("--selector", "<sel>", "Scroll inside this element").short("-s")]),
+    cmd("scrollintoview", "Scroll an element into view", "scrollintoview <selector>")
+        .aliases(&["scrollinto"])
+        .args(SELECTOR_ONLY),
+    // === Wait ===
+    cmd("wait", "Wait for an element, a time, a URL, a load state, a condition or text", "wait <selector|ms|--url|--load|--fn|--text>")
+        .args(&[arg("target", "Selector to wait for, or milliseconds to sleep")
+            .optional()
+            .complete(Complete::Refs)])
+        .options(&[
+            opt("--url", "<pattern>", "Wait for the URL to match a glob").short("-u"),
+            opt("--load", "<state>", "Wait for a load state")
+                .short("-l")
+                .values(LOAD_STATES),
+            opt("--fn", "<expression>", "Wait for a JavaScript expression to be truthy").short("-f"),
+            opt("--text", "<text>", "Wait for text to appear").short("-t"),
+            flag("--download", "Wait for a download (optionally followed by a save path)").short("-d"),
+            opt("--timeout", "<ms>", "Download timeout").int(),
+        ]),
+    // === Screenshot/PDF ===
+    cmd("screenshot", "Take a screenshot of the page or an element", "screenshot [selector] [path]")
+        .args(&[
+            arg("selector", "Element to capture").optional().complete(Complete::Refs),
+            arg("path", "Output file (default: temp file)").optional(),
+        ])
+        .options(&[
+            flag("--full", "Capture the full page").short("-f"),
+            flag("--annotate", "Label interactive elements with their refs"),
+        ]),
+    cmd("pdf", "Save the page as PDF", "pdf <path>").args(&[arg("path", "Output file")]),
+    // === Snapshot ===
+    cmd("snapshot", "Accessibility tree with refs (for AI)", "snapshot [options]").options(&[
+        flag("--interactive", "Only interactive elements").short("-i"),
+        flag("--compact", "Remove empty structural elements").short("-c"),
+        flag("--cursor", "Include cursor-interactive elements").short("-C"),
+        opt("--depth", "<n>", "Limit tree depth").short("-d").int(),
+        opt("--selector", "<sel>", "Scope to a CSS selector").short("-s"),
+    ]),
+    // === Eval ===
+    cmd("eval", "Run JavaScript in the page", "eval [options] <script>")
+        .args(&[arg("script", "JavaScript to evaluate").variadic()])
+        .options(&[
+            flag("--base64", "Decode the script from base64").short("-b"),
+            flag("--stdin", "Read the script from stdin"),
+            SAVE_AS,
+        ]),
+    cmd("close", "Close the browser", "close").aliases(&["quit", "exit"]),
+    // === Auth vault ===
+    cmd("auth", "Manage saved login profiles", "auth <save|login|list|delete|show> ...").subcommands(&[
+        cmd("save", "Save an auth profile", "auth save <name> --url <url> --username <user> --password <pass> [--password-stdin]")
+            .args(&[arg("name", "Profile name")])
+            .options(&[
+                opt("--url", "<url>", "Login page URL"),
+                opt("--username", "<user>", "Username"),
+                opt("--password", "<pass>", "Password (prefer --password-stdin)"),
+                flag("--password-stdin", "Read the password from stdin"),
+                opt("--username-selector", "<sel>", "Username field selector"),
+                opt("--password-selector", "<sel>", "Password field selector"),
+                opt("--submit-selector", "<sel>", "Submit button selector"),
+            ]),
+        cmd("login", "Log in with a saved profile", "auth login <name>")
+            .args(&[arg("name", "Profile name").complete(Complete::AuthProfiles)]),
+        cmd("list", "List saved profiles", "auth list"),
+        cmd("delete", "Delete a profile", "auth delete <name>")
+            .aliases(&["remove"])
+            .args(&[arg("name", "Profile name").complete(Complete::AuthProfiles)]),
+        cmd("show", "Show profile metadata", "auth show <name>")
+            .args(&[arg("name", "Profile name").complete(Complete::AuthProfiles)]),
+    ]),
+    // === Confirmation ===
+    cmd("confirm", "Approve a pending action", "confirm <confirmation-id>")
+        .args(&[arg("confirmation-id", "ID from the confirmation request")]),
+    cmd("deny", "Deny a pending action", "deny <confirmation-id>")
+        .args(&[arg("confirmation-id", "ID from the confirmation request")]),
+    // === Connect ===
+    cmd("connect", "Connect to a browser via CDP", "connect <port|url>")
+        .args(&[arg("endpoint", "CDP port or ws:// / http:// URL")]),
+    // === Get ===
+    cmd("get", "Retrieve information from elements or the page", "get <subcommand> [args]").subcommands(&[
+        cmd("text", "Text content of an element", "get text <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("html", "Inner HTML of an element", "get html <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("value", "Value of an input", "get value <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("attr", "Attribute value", "get attr <selector> <attribute>")
+            .args(&[SELECTOR, arg("attribute", "Attribute name")])
+            .options(SELECTOR_SAVE_AS),
+        cmd("url", "Current URL", "get url").options(SELECTOR_SAVE_AS),
+        cmd("title", "Page title", "get title").options(SELECTOR_SAVE_AS),
+        cmd("count", "Number of matching elements", "get count <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("box", "Bounding box (x, y, width, height)", "get box <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("styles", "Computed styles", "get styles <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+    ]),
+    // === Is ===
+    cmd("is", "Check element state", "is <visible|enabled|checked> <selector>").subcommands(&[
+        cmd("visible", "Whether an element is visible", "is visible <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("enabled", "Whether an element is enabled", "is enabled <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+        cmd("checked", "Whether a checkbox/radio is checked", "is checked <selector>")
+            .args(SELECTOR_ONLY)
+            .options(SELECTOR_SAVE_AS),
+    ]),
+    // === Assert ===
+    cmd("assert", "Check a condition, retrying until it holds (exit code 7 on failure)", "assert <check> [selector] [options]").subcommands(&[
+        cmd("text", "Element text", "assert text <selector> --contains|--equals|--matches <value> [--timeout <ms>]")
+            .args(SELECTOR_ONLY)
+            .options(TEXT_MATCHERS),
+        cmd("url", "Current URL", "assert url --contains|--equals|--matches <value> [--timeout <ms>]")
+            .options(TEXT_MATCHERS),
+        cmd("title", "Page title", "assert title --contains|--equals|--matches <value> [--timeout <ms>]")
+            .options(TEXT_MATCHERS),
+        cmd("count", "Number of matching elements", "assert count <selector> --eq|--gt|--lt <n> [--timeout <ms>]")
+            .args(SELECTOR_ONLY)
+            .options(&[
+                opt("--eq", "<n>", "Passes if the count equals n").int(),
+                opt("--gt", "<n>", "Passes if the count is greater than n").int(),
+                opt("--lt", "<n>", "Passes if the count is less than n").int(),
+                ASSERT_TIMEOUT,
+            ]),
+        cmd("visible", "Element is visible", "assert visible <selector> [--timeout <ms>]")
+            .args(SELECTOR_ONLY)
+            .options(&[ASSERT_TIMEOUT]),
+        cmd("no-console-errors", "No console errors were logged", "assert no-console-errors"),
+    ]),
+    // === Find ===
+    cmd("find", "Find elements with semantic locators and act on them", "find <locator> <value> [action] [text]").subcommands(&[
+        cmd("role", "By ARIA role", "find role <role> [action] [--name <name>] [--exact]")
+            .args(&[arg("role", "ARIA role, e.g. button"), FIND_ACTION, FIND_TEXT])
+            .options(&[opt("--name", "<name>", "Accessible name"), FIND_EXACT]),
+        cmd("text", "By text content", "find text <text> [action] [--exact]")
+            .args(&[arg("value", "Text to find"), FIND_ACTION])
+            .options(&[FIND_EXACT]),
+        cmd("label", "By label", "find label <label> [action] [text] [--exact]")
+            .args(&[arg("label", "Label text"), FIND_ACTION, FIND_TEXT])
+            .options(&[FIND_EXACT]),
+        cmd("placeholder", "By placeholder", "find placeholder <text> [action] [text] [--exact]")
+            .args(&[arg("placeholder", "Placeholder text"), FIND_ACTION, FIND_TEXT])
+            .options(&[FIND_EXACT]),
+        cmd("alt", "By alt text", "find alt <text> [action] [--exact]")
+            .args(&[arg("value", "Alt text"), FIND_ACTION])
+            .options(&[FIND_EXACT]),
+        cmd("title", "By title attribute", "find title <text> [action] [--exact]")
+            .args(&[arg("value", "Title text"), FIND_ACTION])
+            .options(&[FIND_EXACT]),
+        cmd("testid", "By data-testid", "find testid <id> [action] [text]")
+            .args(&[arg("id", "Test ID"), FIND_ACTION, FIND_TEXT]),
+        cmd("first", "First match of a selector", "find first <selector> [action] [text]")
+            .args(&[SELECTOR, FIND_ACTION, FIND_TEXT]),
+        cmd("last", "Last match of a selector", "find last <selector> [action] [text]")
+            .args(&[SELECTOR, FIND_ACTION, FIND_TEXT]),
+        cmd("nth", "Nth match of a selector (0-based, negative from the end)", "find nth <index> <selector> [action] [text]")
+            .args(&[arg("index", "Index").int(), SELECTOR, FIND_ACTION, FIND_TEXT]),
+    ]),
+    // === Mouse ===
+    cmd("mouse", "Low-level mouse control", "mouse <move|down|up|wheel> [args...]").subcommands(&[
+        cmd("move", "Move the mouse", "mouse move <x> <y>")
+            .args(&[arg("x", "X coordinate").int(), arg("y", "Y coordinate").int()]),
+        cmd("down", "Press a mouse button", "mouse down [button]").args(&[arg("button", "Button (default: left)")
+            .optional()
+            .values(MOUSE_BUTTONS)]),
+        cmd("up", "Release a mouse button", "mouse up [button]").args(&[arg("button", "Button (default: left)")
+            .optional()
+            .values(MOUSE_BUTTONS)]),
+        cmd("wheel", "Scroll the mouse wheel", "mouse wheel [dy] [dx]").args(&[
+            arg("dy", "Vertical delta (default: 100)").optional().int(),
+            arg("dx", "Horizontal delta (default: 0)").optional().int(),
+        ]),
+    ]),
+    // === Settings ===
+    cmd("set", "Change browser settings", "set <setting> [value]").subcommands(&[
+        cmd("viewport", "Viewport size", "set viewport <width> <height>")
+            .args(&[arg("width", "Width in pixels").int(), arg("height", "Height in pixels").int()]),
+        cmd("device", "Emulate a device", "set device <name>")
+            .args(&[arg("name", "Device name, e.g. \"iPhone 14\"")]),
+        cmd("geo", "Geolocation", "set geo <latitude> <longitude>")
+            .aliases(&["geolocation"])
+            .args(&[arg("latitude", "Latitude").number(), arg("longitude", "Longitude").number()]),
+        cmd("offline", "Offline mode", "set offline [on|off]")
+            .args(&[arg("state", "on (default) or off").optional().values(ON_OFF)]),
+        cmd("network", "Network throttling", "set network <slow-3g|fast-3g|4g|off> | set network custom --download <kbps> --upload <kbps> --latency <ms>")
+            .args(&[arg("profile", "Throttling profile").values(NETWORK_PROFILES)])
+            .options(&[
+                opt("--download", "<kbps>", "Download throughput").number(),
+                opt("--upload", "<kbps>", "Upload throughput").number(),
+                opt("--latency", "<ms>", "Added latency").number(),
+            ]),
+        cmd("cpu", "CPU throttling", "set cpu <slowdown-factor|off>")
+            .args(&[arg("factor", "Slowdown factor >= 1, or off")]),
+        cmd("timeout", "Operation timeouts", "set timeout <default|navigation|action|wait> <ms>")
+            .args(&[
+                arg("kind", "Which timeout to change").values(TIMEOUT_KINDS),
+                arg("ms", "Timeout in milliseconds").int(),
+            ]),
+        cmd("headers", "Extra HTTP headers", "set headers <json>")
+            .args(&[arg("json", "JSON object of headers")]),
+        cmd("credentials", "HTTP basic auth", "set credentials <username> <password>")
+            .aliases(&["auth"])
+            .args(&[arg("username", "Username"), arg("password", "Password")]),
+        cmd("media", "Emulate media features", "set media [dark|light] [reduced-motion]")
+            .args(&[arg("features", "Media features").optional().variadic().values(MEDIA_FEATURES)]),
+    ]),
+    // === Network ===
+    cmd("network", "Intercept and inspect network traffic", "network <route|unroute|requests|export> [args...]").subcommands(&[
+        cmd("route", "Intercept requests matching a URL", "network route <url> [--abort|--body <json>]")
+            .args(&[arg("url", "URL glob")])
+            .options(&[
+                flag("--abort", "Abort matching requests"),
+                opt("--body", "<json>", "Respond with this body"),
+            ]),
+        cmd("unroute", "Remove a route", "network unroute [url]")
+            .args(&[arg("url", "URL glob (default: all)").optional()]),
+        cmd("requests", "List tracked requests", "network requests [--clear] [--filter <pattern>]")
+            .options(&[
+                flag("--clear", "Clear tracked requests"),
+                opt("--filter", "<pattern>", "Only URLs containing this"),
+            ]),
+        cmd("export", "Export a request as code", "network export [<index>] [--filter <pattern>] [--as <curl|fetch|httpie|python-requests>] [--redact-auth]")
+            .args(&[arg("index", "Request index (default: last)").optional().int()])
+            .options(&[
+                opt("--filter", "<pattern>", "Only URLs containing this"),
+                opt("--as", "<format>", "Output format (default: curl)").values(EXPORT_FORMATS),
+                flag("--redact-auth", "Redact credentials"),
+            ]),
+    ]),
+    // === Storage ===
+    cmd("storage", "Read and write web storage", "storage <local|session> [get|set|clear] [key] [value]").subcommands(&[
+        cmd("local", "localStorage", "storage local [get|set|clear] [key] [value]").args(&[
+            arg("operation", "Operation (default: get)").optional().values(STORAGE_OPERATIONS),
+            arg("key", "Key").optional(),
+            arg("value", "Value for set").optional(),
+        ]),
+        cmd("session", "sessionStorage", "storage session [get|set|clear] [key] [value]").args(&[
+            arg("operation", "Operation (default: get)").optional().values(STORAGE_OPERATIONS),
+            arg("key", "Key").optional(),
+            arg("value", "Value for set").optional(),
+        ]),
+    ]),
+    cmd("cookies", "Manage cookies", "cookies [get|set|clear]").subcommands(&[
+        cmd("get", "List cookies", "cookies get"),
+        cmd("set", "Set a cookie", "cookies set <name> <value> [--url <url>] [--domain <domain>] [--path <path>] [--httpOnly] [--secure] [--sameSite <Strict|Lax|None>] [--expires <timestamp>]")
+            .args(&[arg("name", "Cookie name"), arg("value", "Cookie value")])
+            .options(&[
+                opt("--url", "<url>", "URL the cookie applies to"),
+                opt("--domain", "<domain>", "Cookie domain"),
+                opt("--path", "<path>", "Cookie path"),
+                flag("--httpOnly", "HTTP-only cookie"),
+                flag("--secure", "Secure cookie"),
+                opt("--sameSite", "<policy>", "SameSite policy").values(SAME_SITE),
+                opt("--expires", "<timestamp>", "Expiry as a Unix timestamp").int(),
+            ]),
+        cmd("clear", "Delete all cookies", "cookies clear"),
+    ]),
+    // === Tabs & windows ===
+    cmd("tab", "Manage tabs; a number switches to that tab", "tab [new|list|close|<n>]")
+        .args(&[arg("index", "Tab to switch to").optional().int()])
+        .subcommands(&[
+            cmd("new", "Open a new tab", "tab new [url]")
+                .args(&[arg("url", "URL to open").optional()]),
+            cmd("list", "List tabs", "tab list"),
+            cmd("close", "Close a tab", "tab close [index]")
+                .args(&[arg("index", "Tab index (default: current)").optional().int()]),
+        ]),
+    cmd("window", "Manage windows", "window <new>")
+        .subcommands(&[cmd("new", "Open a new window", "window new")]),
+    cmd("frame", "Switch to an iframe, or back to the main frame", "frame <selector|main>")
+        .args(&[arg("selector", "iframe selector, or main")]),
+    cmd("dialog", "Handle a JavaScript dialog", "dialog <accept|dismiss> [text]").subcommands(&[
+        cmd("accept", "Accept the dialog", "dialog accept [text]")
+            .args(&[arg("text", "Prompt response").optional()]),
+        cmd("dismiss", "Dismiss the dialog", "dialog dismiss"),
+    ]),
+    // === Debug ===
+    cmd("trace", "Record a Playwright trace", "trace <start|stop> [path]").subcommands(&[
+        cmd("start", "Start tracing", "trace start"),
+        cmd("stop", "Stop and save the trace", "trace stop [path]")
+            .args(&[arg("path", "Output file").optional()]),
+    ]),
+    cmd("profiler", "Record a Chrome DevTools profile", "profiler <start|stop> [options]").subcommands(&[
+        cmd("start", "Start profiling", "profiler start [--categories <list>]")
+            .options(&[opt("--categories", "<list>", "Trace categories, comma separated")]),
+        cmd("stop", "Stop and save the profile", "profiler stop [path]")
+            .args(&[arg("path", "Output file").optional()]),
+    ]),
+    cmd("record", "Record video (WebM)", "record <start|stop|restart> [path] [url]").subcommands(&[
+        cmd("start", "Start recording", "record start <output.webm> [url]")
+            .args(&[arg("path", "Output file"), arg("url", "URL to open").optional()]),
+        cmd("stop", "Stop and save the video", "record stop"),
+        cmd("restart", "Save the current video and start a new one", "record restart <output.webm> [url]")
+            .args(&[arg("path", "Output file"), arg("url", "URL to open").optional()]),
+    ]),
+    cmd("console", "View console logs", "console [--clear]")
+        .options(&[flag("--clear", "Clear the log")]),
+    cmd("errors", "View page errors", "errors [--clear]")
+        .options(&[flag("--clear", "Clear the log")]),
+    cmd("highlight", "Highlight an element", "highlight <selector>").args(SELECTOR_ONLY),
+    cmd("more", "Page through the last snapshot, text, HTML or eval output", "more [--page <n>|--offset <chars>]"),
+    // === State ===
+    cmd("state", "Save and restore storage state", "state <save|load|list|clear|show|clean|rename> ...").subcommands(&[
+        cmd("save", "Save cookies and storage to a file", "state save <path>")
+            .args(&[arg("path", "Output file")]),
+        cmd("load", "Load cookies and storage from a file", "state load <path>")
+            .args(&[arg("path", "State file")]),
+        cmd("list", "List saved states", "state list"),
+        cmd("clear", "Delete saved state", "state clear [session-name] [--all]")
+            .args(&[arg("session-name", "Session whose state to delete").optional()])
+            .options(&[flag("--all", "Delete all saved states").short("-a")]),
+        cmd("show", "Show a saved state", "state show <filename>")
+            .args(&[arg("filename", "State file name").complete(Complete::StateFiles)]),
+        cmd("clean", "Delete old states", "state clean --older-than <days>")
+            .options(&[opt("--older-than", "<days>", "Age in days").int()]),
+        cmd("rename", "Rename a saved state", "state rename <old-name> <new-name>")
+            .args(&[
+                arg("old-name", "Current name").complete(Complete::States),
+                arg("new-name", "New name"),
+            ]),
+    ]),
+    // === iOS ===
+    cmd("tap", "Tap an element (iOS)", "tap <selector>").args(SELECTOR_ONLY),
+    cmd("swipe", "Swipe (iOS)", "swipe <up|down|left|right> [distance]").args(&[
+        arg("direction", "Swipe direction").values(DIRECTIONS),
+        arg("distance", "Distance in pixels").optional().int(),
+    ]),
+    cmd("device", "iOS devices", "device list")
+        .subcommands(&[cmd("list", "List available devices", "device list")]),
+    // === Diff ===
+    cmd("diff", "Compare snapshots, screenshots or pages", "diff <snapshot|screenshot|url>").subcommands(&[
+        cmd("snapshot", "Compare the current snapshot with the last one or a baseline", "diff snapshot [--baseline <file>] [--selector <sel>] [--compact] [--depth <n>]")
+            .options(&[
+                opt("--baseline", "<file>", "Baseline snapshot file").short("-b"),
+                opt("--selector", "<sel>", "Scope to a CSS selector").short("-s"),
+                flag("--compact", "Remove empty structural elements").short("-c"),
+                opt("--depth", "<n>", "Limit tree depth").short("-d").int(),
+            ]),
+        cmd("screenshot", "Compare a screenshot with a baseline image", "diff screenshot --baseline <file> [--output <file>] [--threshold <0-1>] [--selector <sel>] [--full]")
+            .options(&[
+                opt("--baseline", "<file>", "Baseline image").short("-b"),
+                opt("--output", "<file>", "Where to write the diff image").short("-o"),
+                opt("--threshold", "<0-1>", "Color difference threshold").short("-t").number(),
+                opt("--selector", "<sel>", "Capture only this element").short("-s"),
+                flag("--full", "Capture the full page"),
+            ]),
+        cmd("url", "Compare two pages", "diff url <url1> <url2> [--screenshot] [--full] [--wait-until <strategy>] [--selector <sel>] [--compact] [--depth <n>]")
+            .args(&[arg("url1", "First URL"), arg("url2", "Second URL")])
+            .options(&[
+                flag("--screenshot", "Also compare screenshots"),
+                flag("--full", "Capture full pages"),
+                opt("--wait-until", "<strategy>", "When a page counts as loaded").values(LOAD_STATES),
+                opt("--selector", "<sel>", "Scope to a CSS selector").short("-s"),
+                flag("--compact", "Remove empty structural elements").short("-c"),
+                opt("--depth", "<n>", "Limit tree depth").short("-d").int(),
+            ]),
+    ]),
+    // === Workflows ===
+    cmd("run", "Run a workflow file", "run <flow.yaml|flow.json> [--var <key=value>]...")
+        .args(&[arg("file", "Workflow file (.yaml, .yml or .json)")])
+        .options(&[opt("--var", "<key=value>", "Set or override a variable").repeatable()]),
+    // === Local commands (no daemon) ===
+    cmd("session", "Show the current session, or list active sessions", "session [list]")
+        .subcommands(&[cmd("list", "List active sessions", "session list")]),
+    cmd("vars", "Manage variables saved with --save-as", "vars [list|clear [name]]").subcommands(&[
+        cmd("list", "List saved variables", "vars list"),
+        cmd("clear", "Remove one variable, or all of them", "vars clear [name]")
+            .args(&[arg("name", "Variable to remove").optional()]),
+    ]),
+    cmd("install", "Install browser binaries", "install [--with-deps]")
+        .options(&[flag("--with-deps", "Also install system dependencies (Linux)").short("-d")]),
+    cmd("doctor", "Check certificates and resolver configuration", "doctor"),
+    cmd("config", "Inspect, validate and edit config files", "config [show|validate|init|get|set]").subcommands(&[
+        cmd("show", "Print effective settings", "config show [--sources]")
+            .options(&[flag("--sources", "Include unset settings and where each value came from")]),
+        cmd("validate", "Check a config file, rejecting unknown keys", "config validate <file>")
+            .args(&[arg("file", "Config file to check")]),
+        cmd("init", "Write an annotated config template", "config init [--project] [--force]").options(&[
+            flag("--project", "Write ./agent-browser.json instead of the user config"),
+            flag("--force", "Overwrite an existing file"),
+        ]),
+        cmd("get", "Print a value from the user config", "config get <key>")
+            .args(&[arg("key", "Config key, e.g. maxOutput")]),
+        cmd("set", "Set a value in the user config", "config set <key> <value> [--force]")
+            .args(&[
+                arg("key", "Config key, e.g. maxOutput"),
+                arg("value", "JSON value, string, or comma-separated list"),
+            ])
+            .options(&[flag("--force", "Rewrite a config file that contains comments")]),
+    ]),
+    cmd("schema", "Print the command schema for agent tool definitions", "schema [--format json-schema|openai-tools|anthropic-tools]")
+        .options(&[opt("--format", "<format>", "Output format (default: json-schema)").values(SCHEMA_FORMATS)]),
+    cmd("completions", "Print a shell completion script", "completions <bash|zsh|fish>")
+        .args(&[arg("shell", "Shell to generate for").values(SHELLS)]),
+];
+
+pub const GLOBAL_OPTIONS: &[Opt] = &[
+    opt(
+        "--session",
+        "<name>",
+        "Isolated session (or AGENT_BROWSER_SESSION env)",
+    )
+    .complete(Complete::Sessions),
+    opt(
+        "--session-name",
+        "<name>",
+        "Auto-save/restore session state (cookies, localStorage)",
+    ),
+    opt("--profile", "<path>", "Persistent browser profile"),
+    opt("--state", "<path>", "Load storage state from a JSON file"),
+    opt(
+        "--headers",
+        "<json>",
+        "HTTP headers scoped to the URL's origin",
+    ),
+    opt("--executable-path", "<path>", "Custom browser executable"),
+    opt("--extension", "<path>", "Load a browser extension").repeatable(),
+    opt(
+        "--args",
+        "<args>",
+        "Browser launch args, comma or newline separated",
+    ),
+    opt("--user-agent", "<ua>", "Custom User-Agent"),
+    opt("--proxy", "<server>", "Proxy server URL"),
+    opt(
+        "--proxy-bypass",
+        "<hosts>",
+        "Bypass the proxy for these hosts",
+    ),
+    opt("--proxy-pac", "<url|file>", "Proxy auto-config script"),
+    opt(
+        "--proxy-password-env",
+        "<var>",
+        "Read the proxy password from this env var",
+    ),
+    opt(
+        "--proxy-list",
+        "<file>",
+        "Proxy URLs, one per line; stable per session",
+    ),
+    flag(
+        "--proxy-rotate",
+        "Use the next proxy from --proxy-list on each navigation (restarts the context: other tabs and sessionStorage are lost)",
+    ),
+    flag("--ignore-https-errors", "Ignore HTTPS certificate errors"),
+    opt("--ca-cert", "<pem>", "Trust certificates from a PEM file").repeatable(),
+    opt(
+        "--resolve",
+        "<host:port:ip>",
+        "Resolve host:port to ip, like curl --resolve",
+    )
+    .repeatable(),
+    opt(
+        "--client-cert",
+        "<spec>",
+        "mTLS cert: origin=<url>,cert=<pem>,key=<pem>[,passphrase-env=<VAR>]",
+    )
+    .repeatable(),
+    flag(
+        "--allow-file-access",
+        "Allow file:// URLs to access local files",
+    ),
+    opt("--provider", "<name>", "Browser provider")
+        .short("-p")
+        .values(&["ios", "browserbase", "kernel", "browseruse"]),
+    opt("--device", "<name>", "iOS device name"),
+    flag("--json", "JSON output"),
+    flag("--full", "Full page screenshot").short("-f"),
+    flag("--annotate", "Annotated screenshot with numbered labels"),
+    flag("--headed", "Show the browser window"),
+    opt("--cdp", "<port|url>", "Connect via CDP"),
+    flag(
+        "--auto-connect",
+        "Auto-discover and connect to a running Chrome",
+    ),
+    opt("--color-scheme", "<scheme>", "Color scheme").values(COLOR_SCHEMES),
+    opt("--download-path", "<path>", "Default download directory"),
+    flag(
+        "--content-boundaries",
+        "Wrap page output in boundary markers",
+    ),
+    opt(
+        "--injection-scan",
+        "<mode>",
+        "Flag likely prompt injection in page output (warn, strip)",
+    )
+    .values(INJECTION_SCAN_MODES),
+    opt("--max-output", "<chars>", "Truncate page output to N chars").int(),
+    opt("--max-tokens", "<n>", "Truncate page output to about N tokens").int(),
+    opt("--page", "<n>", "Show page N of truncated page output").int(),
+    opt("--offset", "<chars>", "Start page output at this character").int(),
+    opt("--default-timeout", "<ms>", "Default Playwright timeout for actions and waits").int(),
+    opt(
+        "--allowed-domains",
+        "<list>",
+        "Restrict navigation to these domains",
+    ),
+    opt(
+        "--block",
+        "<types>",
+        "Abort images, fonts, media, stylesheets",
+    ),
+    opt("--action-policy", "<path>", "Action policy JSON file"),
+    opt(
+        "--confirm-actions",
+        "<list>",
+        "Action categories requiring confirmation",
+    ),
+    flag("--confirm-interactive", "Interactive confirmation prompts"),
+    opt(
+        "--report",
+        "<junit|tap>[:path]",
+        "Test report for assert commands and workflow steps",
+    ),
+    opt("--field", "<path>", "Print only this response field, e.g. data.url"),
+    opt("--format", "<template>", "Render the response, e.g. '{{title}} | {{url}}'"),
+    opt("--out", "<path>", "Write the result to a file and print its size and sha256"),
+    opt("--config", "<path>", "Use a custom config file"),
+    opt("--env", "<name>", "Apply a named profile from the config's profiles"),
+    flag("--debug", "Trace config, daemon startup and protocol frames to stderr"),
+    flag(
+        "--dry-run",
+        "Print the JSON commands that would be sent, without starting a daemon",
+    ),
+];
+
+/// Look up a top-level command by name or alias.
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.matches(name))
+}
+
+/// Subcommand names (and aliases) of a top-level command, for "Valid options" errors.
+pub fn subcommand_names(command: &str) -> Vec<&'static str> {
+    find(command)
+        .map(Command::subcommand_names)
+        .unwrap_or_default()
+}
+
+/// Look up a global flag by long or short name.
+pub fn global_option(name: &str) -> Option<&'static Opt> {
+    GLOBAL_OPTIONS
+        .iter()
+        .find(|o| o.name == name || o.short == Some(name))
+}
+
+/// Every command that can be invoked on its own, with its path: commands
+/// without subcommands, subcommands, and parents that also take arguments
+/// (like `tab <n>`).
+pub fn leaves() -> Vec<(Vec<&'static str>, &'static Command)> {
+    fn walk(
+        prefix: &[&'static str],
+        commands: &'static [Command],
+        out: &mut Vec<(Vec<&'static str>, &'static Command)>,
+    ) {
+        for command in commands {
+            let mut path = prefix.to_vec();
+            path.push(command.name);
+            if command.subcommands.is_empty() || !command.args.is_empty() {
+                out.push((path.clone(), command));
+            }
+            walk(&path, command.subcommands, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(&[], COMMANDS, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{parse_command, ParseError};
+    use crate::flags::parse_flags;
+    use crate::output::command_help;
+
+    /// Handled in main before parse_command
+    const LOCAL_COMMANDS: &[&str] = &[
+        "session",
+        "vars",
+        "install",
+        "doctor",
+        "config",
+        "schema",
+        "completions",
+    ];
+
+    fn parse(args: &[&str]) -> Result<serde_json::Value, ParseError> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_command(&args, &parse_flags(&[]))
+    }
+
+    #[test]
+    fn test_every_command_is_parsed() {
+        for command in COMMANDS {
+            if LOCAL_COMMANDS.contains(&command.name) {
+                continue;
+            }
+            for name in std::iter::once(&command.name).chain(command.aliases) {
+                // "eval --stdin" would block on stdin; a bare name never reads it
+                let result = parse(&[name]);
+                assert!(
+                    !matches!(result, Err(ParseError::UnknownCommand { .. })),
+                    "registry command '{}' is not handled by parse_command",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_subcommand_is_parsed() {
+        for command in COMMANDS {
+            if LOCAL_COMMANDS.contains(&command.name) {
+                continue;
+            }
+            for sub in command.subcommand_names() {
+                let result = parse(&[command.name, sub]);
+                assert!(
+                    !matches!(result, Err(ParseError::UnknownSubcommand { .. })),
+                    "registry subcommand '{} {}' is rejected by parse_command",
+                    command.name,
+                    sub
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_subcommand_lists_registry_names() {
+        match parse(&["get", "nope"]) {
+            Err(ParseError::UnknownSubcommand { valid_options, .. }) => {
+                assert_eq!(valid_options, subcommand_names("get"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_every_command_has_help() {
+        for command in COMMANDS {
+            assert!(
+                command_help(command.name).is_some(),
+                "no help for '{}'",
+                command.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        let mut names: Vec<&str> = COMMANDS
+            .iter()
+            .flat_map(|c| std::iter::once(c.name).chain(c.aliases.iter().copied()))
+            .collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn test_leaves() {
+        let paths: Vec<String> = leaves().iter().map(|(p, _)| p.join(" ")).collect();
+        assert!(paths.contains(&"get text".to_string()));
+        assert!(paths.contains(&"tab".to_string()));
+        assert!(paths.contains(&"tab new".to_string()));
+        assert!(!paths.contains(&"get".to_string()));
+    }
+
+    #[test]
+    fn test_global_option() {
+        assert_eq!(global_option("-p").unwrap().name, "--provider");
+        assert!(global_option("--json").unwrap().value.is_none());
+        assert!(global_option("--new-tab").is_none());
+    }
+}
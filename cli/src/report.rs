@@ -0,0 +1,391 @@
+use crate::connection::{get_socket_dir, Response};
+use crate::proxy::fnv1a;
+use crate::workflow::{StepResult, StepStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+}
+
+/// Parsed `--report` value: `junit[:path]` or `tap[:path]`. Without a path
+/// the report is written to stdout in place of the regular output.
+#[derive(Debug)]
+pub struct ReportSpec {
+    pub format: ReportFormat,
+    pub path: Option<PathBuf>,
+}
+
+/// One reported check: an `assert` command or a workflow step.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    pub name: String,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+}
+
+impl From<&StepResult> for TestCase {
+    fn from(r: &StepResult) -> Self {
+        TestCase {
+            name: r.label.clone(),
+            status: r.status,
+            duration_ms: r.duration.as_millis() as u64,
+            message: r.error.clone(),
+            attachments: r.attachments.clone(),
+        }
+    }
+}
+
+impl ReportSpec {
+    pub fn parse(spec: &str) -> Result<ReportSpec, String> {
+        let (format, path) = match spec.split_once(':') {
+            Some((f, p)) => (f, Some(p)),
+            None => (spec, None),
+        };
+        let format = match format {
+            "junit" => ReportFormat::Junit,
+            "tap" => ReportFormat::Tap,
+            other => {
+                return Err(format!(
+                    "Invalid --report format '{}': expected junit[:path] or tap[:path]",
+                    other
+                ))
+            }
+        };
+        let path = match path {
+            Some("") => return Err("--report: empty path".to_string()),
+            Some(p) => Some(absolute(Path::new(p))),
+            None => None,
+        };
+        Ok(ReportSpec { format, path })
+    }
+
+    pub fn to_stdout(&self) -> bool {
+        self.path.is_none()
+    }
+
+    /// Directory for failure screenshots and traces: `<report>-artifacts`
+    /// next to the report file, or `agent-browser-artifacts` for stdout.
+    fn artifacts_dir(&self) -> PathBuf {
+        match &self.path {
+            Some(p) => {
+                let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("report");
+                p.with_file_name(format!("{}-artifacts", stem))
+            }
+            None => absolute(Path::new("agent-browser-artifacts")),
+        }
+    }
+
+    /// Render and write (or print) a report for a complete run.
+    pub fn write(&self, suite: &str, cases: &[TestCase]) -> Result<(), String> {
+        let content = match self.format {
+            ReportFormat::Junit => render_junit(suite, cases),
+            ReportFormat::Tap => render_tap(cases),
+        };
+        match &self.path {
+            Some(path) => {
+                if let Some(dir) = path.parent() {
+                    let _ = fs::create_dir_all(dir);
+                }
+                fs::write(path, content)
+                    .map_err(|e| format!("Cannot write report '{}': {}", path.display(), e))
+            }
+            None => {
+                print!("{}", content);
+                Ok(())
+            }
+        }
+    }
+
+    /// Add one case to a report shared across CLI invocations, so a CI script
+    /// running several `assert` commands builds up a single file. Cases are
+    /// kept in a sidecar in the socket dir; deleting the report starts over.
+    pub fn append(&self, suite: &str, case: TestCase) -> Result<(), String> {
+        let Some(ref path) = self.path else {
+            return self.write(suite, &[case]);
+        };
+        let store = get_socket_dir().join(format!("report-{:016x}.json", fnv1a(&path.to_string_lossy())));
+        let mut cases: Vec<TestCase> = if path.exists() {
+            fs::read_to_string(&store)
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        cases.push(case);
+        let _ = fs::create_dir_all(get_socket_dir());
+        let _ = fs::write(&store, serde_json::to_string(&cases).unwrap_or_default());
+        self.write(suite, &cases)
+    }
+
+    /// Capture a screenshot, and the trace since the previous failure if the
+    /// report's own trace is recording, after a failed step. A trace started
+    /// with `trace start` is left alone. Best effort: capture errors are ignored.
+    pub fn capture_failure(
+        &self,
+        name: &str,
+        send: &mut dyn FnMut(Value) -> Result<Response, String>,
+    ) -> Vec<String> {
+        let dir = self.artifacts_dir();
+        if fs::create_dir_all(&dir).is_err() {
+            return Vec::new();
+        }
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let base = format!("{}-{}", slug(name), stamp);
+        let captures = [
+            json!({
+                "action": "screenshot",
+                "path": dir.join(format!("{}.png", base)),
+            }),
+            json!({
+                "action": "trace_chunk",
+                "path": dir.join(format!("{}.trace.zip", base)),
+            }),
+        ];
+        captures
+            .into_iter()
+            .filter_map(|mut cmd| {
+                cmd["id"] = json!(crate::commands::gen_id());
+                let resp = send(cmd).ok().filter(|r| r.success)?;
+                resp.data?.get("path")?.as_str().map(String::from)
+            })
+            .collect()
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map(|d| d.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+/// Filesystem-safe name for artifacts, e.g. "assert text @e1" -> "assert-text-e1".
+fn slug(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+        if out.len() >= 60 {
+            break;
+        }
+    }
+    let out = out.trim_matches('-');
+    if out.is_empty() {
+        "step".to_string()
+    } else {
+        out.to_string()
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline are invalid in XML 1.0
+            c if (c as u32) < 0x20 && c != '\t' && c != '\n' && c != '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn render_junit(suite: &str, cases: &[TestCase]) -> String {
+    let failures = cases.iter().filter(|c| c.status == StepStatus::Failed).count();
+    let skipped = cases.iter().filter(|c| c.status == StepStatus::Skipped).count();
+    let total_ms: u64 = cases.iter().map(|c| c.duration_ms).sum();
+    let suite = xml_escape(suite);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"agent-browser\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        cases.len(),
+        failures,
+        skipped,
+        seconds(total_ms)
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\">\n",
+        suite,
+        cases.len(),
+        failures,
+        skipped,
+        seconds(total_ms)
+    ));
+    for case in cases {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+            xml_escape(&case.name),
+            suite,
+            seconds(case.duration_ms)
+        ));
+        if case.status == StepStatus::Passed && case.attachments.is_empty() {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+        match case.status {
+            StepStatus::Failed => {
+                let message = xml_escape(case.message.as_deref().unwrap_or("failed"));
+                xml.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    message, message
+                ));
+            }
+            StepStatus::Skipped => xml.push_str("      <skipped message=\"condition not met\"/>\n"),
+            StepStatus::Passed => {}
+        }
+        if !case.attachments.is_empty() {
+            // [[ATTACHMENT|path]] is understood by Jenkins and GitLab
+            xml.push_str("      <system-out>");
+            for path in &case.attachments {
+                xml.push_str(&format!("[[ATTACHMENT|{}]]\n", xml_escape(path)));
+            }
+            xml.push_str("</system-out>\n");
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn render_tap(cases: &[TestCase]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", cases.len());
+    for (i, case) in cases.iter().enumerate() {
+        // '#' starts a directive in TAP, so keep it out of descriptions
+        let name = case.name.replace('#', "\\#").replace('\n', " ");
+        match case.status {
+            StepStatus::Passed => tap.push_str(&format!("ok {} - {}\n", i + 1, name)),
+            StepStatus::Skipped => tap.push_str(&format!(
+                "ok {} - {} # SKIP condition not met\n",
+                i + 1,
+                name
+            )),
+            StepStatus::Failed => tap.push_str(&format!("not ok {} - {}\n", i + 1, name)),
+        }
+        if case.status == StepStatus::Skipped {
+            continue;
+        }
+        // YAML diagnostics block
+        tap.push_str("  ---\n");
+        tap.push_str(&format!("  duration_ms: {}\n", case.duration_ms));
+        if let Some(ref message) = case.message {
+            tap.push_str(&format!("  message: {}\n", json!(message)));
+        }
+        if !case.attachments.is_empty() {
+            tap.push_str("  attachments:\n");
+            for path in &case.attachments {
+                tap.push_str(&format!("    - {}\n", json!(path)));
+            }
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cases() -> Vec<TestCase> {
+        vec![
+            TestCase {
+                name: "open https://example.com".to_string(),
+                status: StepStatus::Passed,
+                duration_ms: 1200,
+                message: None,
+                attachments: vec![],
+            },
+            TestCase {
+                name: "assert text @e1 --contains <Welcome>".to_string(),
+                status: StepStatus::Failed,
+                duration_ms: 5003,
+                message: Some("expected contains \"Welcome\", got \"Hi\"".to_string()),
+                attachments: vec!["/tmp/r-artifacts/a.png".to_string()],
+            },
+            TestCase {
+                name: "click @cookie-banner".to_string(),
+                status: StepStatus::Skipped,
+                duration_ms: 30,
+                message: None,
+                attachments: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ReportSpec::parse("junit:out.xml").unwrap();
+        assert_eq!(spec.format, ReportFormat::Junit);
+        assert!(spec.path.unwrap().is_absolute());
+
+        let spec = ReportSpec::parse("tap").unwrap();
+        assert_eq!(spec.format, ReportFormat::Tap);
+        assert!(spec.to_stdout());
+
+        assert!(ReportSpec::parse("html:out.html").is_err());
+        assert!(ReportSpec::parse("junit:").is_err());
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let xml = render_junit("Checkout & pay", &cases());
+        assert!(xml.contains("<testsuite name=\"Checkout &amp; pay\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\" time=\"6.233\">"));
+        assert!(xml.contains("<testcase name=\"open https://example.com\" classname=\"Checkout &amp; pay\" time=\"1.200\"/>"));
+        assert!(xml.contains("name=\"assert text @e1 --contains &lt;Welcome&gt;\""));
+        assert!(xml.contains("<failure message=\"expected contains &quot;Welcome&quot;, got &quot;Hi&quot;\">"));
+        assert!(xml.contains("[[ATTACHMENT|/tmp/r-artifacts/a.png]]"));
+        assert!(xml.contains("<skipped message=\"condition not met\"/>"));
+    }
+
+    #[test]
+    fn test_render_tap() {
+        let tap = render_tap(&cases());
+        assert!(tap.starts_with("TAP version 13\n1..3\n"));
+        assert!(tap.contains("ok 1 - open https://example.com\n"));
+        assert!(tap.contains("not ok 2 - assert text @e1 --contains <Welcome>\n"));
+        assert!(tap.contains("  message: \"expected contains \\\"Welcome\\\", got \\\"Hi\\\"\"\n"));
+        assert!(tap.contains("    - \"/tmp/r-artifacts/a.png\"\n"));
+        assert!(tap.contains("ok 3 - click @cookie-banner # SKIP condition not met\n"));
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("assert text @e1 --contains Hi"), "assert-text-e1-contains-hi");
+        assert_eq!(slug("@@@"), "step");
+    }
+
+    #[test]
+    fn test_xml_escape_strips_control_chars() {
+        assert_eq!(xml_escape("a\u{1b}[31mb"), "a[31mb");
+    }
+}
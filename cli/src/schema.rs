@@ -0,0 +1,260 @@
+use crate::color;
+use crate::exit_code;
+use crate::registry::{self, Arg, Command, Kind, Opt};
+use serde_json::{json, Map, Value};
+use std::process::exit;
+
+fn type_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::String => "string",
+        Kind::Integer => "integer",
+        Kind::Number => "number",
+        Kind::Boolean => "boolean",
+    }
+}
+
+fn value_schema(kind: Kind, values: &[&str], description: &str, array: bool) -> Value {
+    let mut item = json!({ "type": type_name(kind) });
+    if !values.is_empty() {
+        item["enum"] = json!(values);
+    }
+    let mut schema = if array {
+        json!({ "type": "array", "items": item })
+    } else {
+        item
+    };
+    schema["description"] = json!(description);
+    schema
+}
+
+fn arg_schema(arg: &Arg) -> Value {
+    value_schema(arg.kind, arg.values, arg.description, arg.variadic)
+}
+
+fn opt_schema(opt: &Opt) -> Value {
+    value_schema(opt.kind, opt.values, opt.description, opt.repeatable)
+}
+
+/// Property name for a flag: `--save-as` -> `save-as`.
+fn opt_key(opt: &Opt) -> &'static str {
+    opt.name.trim_start_matches('-')
+}
+
+fn object_schema(args: &[Arg], options: &[Opt]) -> Value {
+    let mut properties = Map::new();
+    for arg in args {
+        properties.insert(arg.name.to_string(), arg_schema(arg));
+    }
+    for opt in options {
+        properties.insert(opt_key(opt).to_string(), opt_schema(opt));
+    }
+    let required: Vec<&str> = args.iter().filter(|a| a.required).map(|a| a.name).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Tool name for a command path: `["get", "text"]` -> `get_text`.
+fn tool_name(path: &[&str]) -> String {
+    path.join("_").replace('-', "_")
+}
+
+fn description(command: &Command) -> String {
+    format!(
+        "{}. Usage: agent-browser {}",
+        command.summary, command.usage
+    )
+}
+
+fn json_schema() -> Value {
+    let commands: Vec<Value> = registry::leaves()
+        .into_iter()
+        .map(|(path, command)| {
+            json!({
+                "name": path.join(" "),
+                "tool": tool_name(&path),
+                "aliases": command.aliases,
+                "description": command.summary,
+                "usage": format!("agent-browser {}", command.usage),
+                "arguments": command.args.iter().map(|a| a.name).collect::<Vec<_>>(),
+                "options": command.options.iter().map(|o| o.name).collect::<Vec<_>>(),
+                "parameters": object_schema(command.args, command.options),
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "agent-browser",
+        "version": env!("CARGO_PKG_VERSION"),
+        "globalOptions": object_schema(&[], registry::GLOBAL_OPTIONS),
+        "commands": commands,
+    })
+}
+
+fn openai_tools() -> Value {
+    registry::leaves()
+        .into_iter()
+        .map(|(path, command)| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": tool_name(&path),
+                    "description": description(command),
+                    "parameters": object_schema(command.args, command.options),
+                },
+            })
+        })
+        .collect()
+}
+
+fn anthropic_tools() -> Value {
+    registry::leaves()
+        .into_iter()
+        .map(|(path, command)| {
+            json!({
+                "name": tool_name(&path),
+                "description": description(command),
+                "input_schema": object_schema(command.args, command.options),
+            })
+        })
+        .collect()
+}
+
+/// Build the schema in one of `registry::SCHEMA_FORMATS`.
+pub fn schema(format: &str) -> Option<Value> {
+    match format {
+        "json-schema" => Some(json_schema()),
+        "openai-tools" => Some(openai_tools()),
+        "anthropic-tools" => Some(anthropic_tools()),
+        _ => None,
+    }
+}
+
+fn fail(msg: &str, json_mode: bool) -> ! {
+    if json_mode {
+        println!("{}", json!({ "success": false, "error": msg }));
+    } else {
+        eprintln!("{} {}", color::error_indicator(), msg);
+    }
+    exit(exit_code::USAGE);
+}
+
+/// Handle `schema [--format <format>]` without the daemon. The schema is
+/// printed as-is so it can be redirected straight into a tools file.
+/// `--format` is a global option, so it arrives already parsed.
+pub fn run_schema(args: &[String], format: Option<&str>, json_mode: bool) -> ! {
+    let format = format.unwrap_or("json-schema");
+    if let Some(other) = args.get(1) {
+        fail(
+            &format!("Unknown argument: {}\nUsage: agent-browser schema [--format json-schema|openai-tools|anthropic-tools]", other),
+            json_mode,
+        );
+    }
+    let Some(schema) = schema(format) else {
+        fail(
+            &format!(
+                "Unknown schema format: {}. Valid options: {}",
+                format,
+                registry::SCHEMA_FORMATS.join(", ")
+            ),
+            json_mode,
+        );
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schema).unwrap_or_default()
+    );
+    exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_tool<'a>(tools: &'a Value, name: &str) -> &'a Value {
+        tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == name)
+            .unwrap_or_else(|| panic!("no tool {}", name))
+    }
+
+    #[test]
+    fn test_tool_names_are_unique_and_valid() {
+        let tools = anthropic_tools();
+        let mut names: Vec<&str> = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names
+            .iter()
+            .all(|n| n.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')));
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn test_enums() {
+        let tools = anthropic_tools();
+        let swipe = find_tool(&tools, "swipe");
+        assert_eq!(
+            swipe["input_schema"]["properties"]["direction"]["enum"],
+            json!(["up", "down", "left", "right"])
+        );
+        assert_eq!(swipe["input_schema"]["required"], json!(["direction"]));
+
+        let media = find_tool(&tools, "set_media");
+        let features = &media["input_schema"]["properties"]["features"];
+        assert_eq!(features["type"], "array");
+        assert_eq!(
+            features["items"]["enum"],
+            json!(["dark", "light", "reduced-motion"])
+        );
+    }
+
+    #[test]
+    fn test_options_become_properties() {
+        let tools = anthropic_tools();
+        let props = &find_tool(&tools, "get_text")["input_schema"]["properties"];
+        assert_eq!(props["selector"]["type"], "string");
+        assert_eq!(props["save-as"]["type"], "string");
+        let props = &find_tool(&tools, "snapshot")["input_schema"]["properties"];
+        assert_eq!(props["interactive"]["type"], "boolean");
+        assert_eq!(props["depth"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_openai_format() {
+        let tools = openai_tools();
+        let first = &tools[0];
+        assert_eq!(first["type"], "function");
+        assert_eq!(first["function"]["name"], "open");
+        assert!(first["function"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("Usage: agent-browser open <url>"));
+    }
+
+    #[test]
+    fn test_json_schema_format() {
+        let doc = json_schema();
+        let commands = doc["commands"].as_array().unwrap();
+        let open = commands.iter().find(|c| c["name"] == "open").unwrap();
+        assert_eq!(open["aliases"], json!(["goto", "navigate"]));
+        assert_eq!(open["usage"], "agent-browser open <url>");
+        assert!(commands.iter().any(|c| c["name"] == "tab new"));
+        assert_eq!(
+            doc["globalOptions"]["properties"]["color-scheme"]["enum"],
+            json!(["dark", "light", "no-preference"])
+        );
+        assert!(schema("yaml").is_none());
+    }
+}
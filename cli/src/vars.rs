@@ -0,0 +1,258 @@
+use crate::color;
+use crate::connection::get_socket_dir;
+use crate::exit_code;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+
+/// Commands whose result can be captured with `--save-as`.
+const SAVE_AS_COMMANDS: &[&str] = &["get", "eval", "is"];
+
+fn store_path(session: &str) -> PathBuf {
+    get_socket_dir().join(format!("{}.vars.json", session))
+}
+
+/// Load the session's variable store. Missing or unreadable stores are empty.
+pub fn load(session: &str) -> Map<String, Value> {
+    fs::read_to_string(store_path(session))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(session: &str, vars: &Map<String, Value>) -> Result<(), String> {
+    let _ = fs::create_dir_all(get_socket_dir());
+    let path = store_path(session);
+    fs::write(&path, serde_json::to_string_pretty(vars).unwrap_or_default())
+        .map_err(|e| format!("Cannot write variables to {}: {}", path.display(), e))
+}
+
+pub fn set(session: &str, name: &str, value: Value) -> Result<(), String> {
+    let mut vars = load(session);
+    vars.insert(name.to_string(), value);
+    save(session, &vars)
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Remove `--save-as <name>` from command args. Errors if the name is invalid
+/// or the command does not return a value.
+pub fn take_save_as(args: &mut Vec<String>) -> Result<Option<String>, String> {
+    let Some(pos) = args.iter().position(|a| a == "--save-as") else {
+        return Ok(None);
+    };
+    let name = args
+        .get(pos + 1)
+        .cloned()
+        .ok_or("--save-as requires a variable name")?;
+    if !is_valid_name(&name) {
+        return Err(format!(
+            "Invalid variable name '{}': use letters, digits, '_' or '-', starting with a letter",
+            name
+        ));
+    }
+    let command = args.first().map(String::as_str).unwrap_or_default();
+    if !SAVE_AS_COMMANDS.contains(&command) {
+        return Err(format!(
+            "--save-as is only supported for {} commands",
+            SAVE_AS_COMMANDS.join(", ")
+        ));
+    }
+    args.drain(pos..pos + 2);
+    Ok(Some(name))
+}
+
+/// The value a command produced: the single result field (`text`, `count`,
+/// `visible`, `result`, ...) or the whole data object if there are several.
+pub fn result_value(data: &Value) -> Value {
+    let Value::Object(map) = data else {
+        return data.clone();
+    };
+    let mut fields = map.iter().filter(|(k, _)| k.as_str() != "origin");
+    match (fields.next(), fields.next()) {
+        (Some((_, value)), None) => value.clone(),
+        _ => data.clone(),
+    }
+}
+
+pub fn to_arg(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Replace `${name}` with stored values in each argument. Unknown names are
+/// left as-is so JavaScript template literals in `eval` keep working;
+/// `$${name}` produces a literal `${name}`.
+pub fn expand(args: &[String], vars: &Map<String, Value>) -> Vec<String> {
+    if vars.is_empty() {
+        return args.to_vec();
+    }
+    args.iter()
+        .map(|arg| {
+            let mut out = String::new();
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find("${") {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                let name = &rest[start + 2..start + len];
+                if start > 0 && rest[..start].ends_with('$') && vars.contains_key(name) {
+                    out.push_str(&rest[..start - 1]);
+                    out.push_str(&rest[start..=start + len]);
+                } else if let Some(value) = vars.get(name) {
+                    out.push_str(&rest[..start]);
+                    out.push_str(&to_arg(value));
+                } else {
+                    out.push_str(&rest[..=start + len]);
+                }
+                rest = &rest[start + len + 1..];
+            }
+            out.push_str(rest);
+            out
+        })
+        .collect()
+}
+
+/// Handle `vars list` and `vars clear [name]` without the daemon.
+pub fn run_vars(args: &[String], session: &str, json_mode: bool) -> ! {
+    let sub = args.get(1).map(String::as_str);
+    match sub {
+        Some("list") | None => {
+            let vars = load(session);
+            if json_mode {
+                println!("{}", json!({ "success": true, "data": { "vars": vars } }));
+            } else if vars.is_empty() {
+                println!("{}", color::dim("No variables saved"));
+            } else {
+                for (name, value) in &vars {
+                    println!("{} = {}", color::bold(name), value);
+                }
+            }
+            exit(0);
+        }
+        Some("clear") => {
+            let mut vars = load(session);
+            let result = match args.get(2) {
+                Some(name) => {
+                    if vars.remove(name.as_str()).is_none() {
+                        let msg = format!("Variable '{}' not found", name);
+                        if json_mode {
+                            println!("{}", json!({ "success": false, "error": msg }));
+                        } else {
+                            eprintln!("{} {}", color::error_indicator(), msg);
+                        }
+                        exit(exit_code::ERROR);
+                    }
+                    save(session, &vars)
+                }
+                None => match fs::remove_file(store_path(session)) {
+                    Ok(()) => Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    Err(e) => Err(format!("Cannot clear variables: {}", e)),
+                },
+            };
+            match result {
+                Ok(()) => {
+                    if json_mode {
+                        println!("{}", json!({ "success": true, "data": { "cleared": true } }));
+                    } else {
+                        println!("{} Variables cleared", color::success_indicator());
+                    }
+                    exit(0);
+                }
+                Err(e) => {
+                    if json_mode {
+                        println!("{}", json!({ "success": false, "error": e }));
+                    } else {
+                        eprintln!("{} {}", color::error_indicator(), e);
+                    }
+                    exit(exit_code::ERROR);
+                }
+            }
+        }
+        Some(other) => {
+            let msg = format!("Unknown subcommand: {}. Valid options: list, clear", other);
+            if json_mode {
+                println!("{}", json!({ "success": false, "error": msg }));
+            } else {
+                eprintln!("{} {}", color::error_indicator(), msg);
+            }
+            exit(exit_code::USAGE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn vars() -> Map<String, Value> {
+        json!({ "heading": "Welcome back", "count": 3 })
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_take_save_as() {
+        let mut args = strings(&["get", "text", "@e5", "--save-as", "heading"]);
+        assert_eq!(take_save_as(&mut args).unwrap().as_deref(), Some("heading"));
+        assert_eq!(args, strings(&["get", "text", "@e5"]));
+
+        let mut args = strings(&["click", "@e1"]);
+        assert_eq!(take_save_as(&mut args).unwrap(), None);
+    }
+
+    #[test]
+    fn test_take_save_as_errors() {
+        let mut args = strings(&["click", "@e1", "--save-as", "x"]);
+        assert!(take_save_as(&mut args).unwrap_err().contains("only supported"));
+
+        let mut args = strings(&["get", "url", "--save-as", "1bad"]);
+        assert!(take_save_as(&mut args).unwrap_err().contains("Invalid variable name"));
+
+        let mut args = strings(&["get", "url", "--save-as"]);
+        assert!(take_save_as(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_result_value() {
+        assert_eq!(
+            result_value(&json!({ "text": "Hi", "origin": "https://a.test" })),
+            json!("Hi")
+        );
+        assert_eq!(result_value(&json!({ "visible": false })), json!(false));
+        let box_data = json!({ "x": 1, "y": 2 });
+        assert_eq!(result_value(&box_data), box_data);
+    }
+
+    #[test]
+    fn test_expand() {
+        let args = strings(&["fill", "@e1", "${heading}!", "${count}", "${unknown}", "$${heading}"]);
+        assert_eq!(
+            expand(&args, &vars()),
+            strings(&["fill", "@e1", "Welcome back!", "3", "${unknown}", "${heading}"])
+        );
+    }
+
+    #[test]
+    fn test_expand_leaves_js_templates() {
+        let args = strings(&["eval", "`${a}-${heading}`"]);
+        assert_eq!(
+            expand(&args, &vars()),
+            strings(&["eval", "`${a}-Welcome back`"])
+        );
+    }
+}
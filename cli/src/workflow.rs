@@ -0,0 +1,1096 @@
+use crate::color;
+use crate::commands::{gen_id, parse_command};
+use crate::connection::{read_timeout, DaemonClient, Response};
+use crate::exit_code;
+use crate::flags::{clean_args, Flags};
+use crate::injection;
+use crate::output::{print_response_with_opts, OutputOptions};
+use crate::report::{ReportSpec, TestCase};
+use crate::vars;
+use crate::yaml;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_STEP_TIMEOUT_MS: u64 = 30_000;
+const RETRY_DELAY_MS: u64 = 500;
+
+/// Actions handled locally by the CLI that cannot run inside a workflow.
+const LOCAL_ACTIONS: &[&str] = &["run", "auth_save", "auth_list", "auth_show", "auth_delete"];
+
+pub struct Workflow {
+    pub name: Option<String>,
+    source: String,
+    vars: Map<String, Value>,
+    steps: Vec<Step>,
+    timeout_ms: Option<u64>,
+    retries: u32,
+}
+
+struct Step {
+    name: Option<String>,
+    kind: StepKind,
+    condition: Option<String>,
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    continue_on_error: bool,
+}
+
+enum StepKind {
+    Command(Vec<String>),
+    Loop {
+        rows: Rows,
+        as_name: String,
+        steps: Vec<Step>,
+    },
+}
+
+enum Rows {
+    Inline(Vec<Value>),
+    /// `${name}` reference resolved when the loop runs
+    Var(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            StepStatus::Passed => "passed",
+            StepStatus::Failed => "failed",
+            StepStatus::Skipped => "skipped",
+        }
+    }
+}
+
+pub struct StepResult {
+    pub index: usize,
+    pub label: String,
+    pub action: Option<String>,
+    pub status: StepStatus,
+    pub duration: Duration,
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub data: Option<Value>,
+    /// Failure screenshot and trace captured for reports
+    pub attachments: Vec<String>,
+}
+
+impl Workflow {
+    /// Load a workflow from a `.json`, `.yaml` or `.yml` file. `cli_vars` come
+    /// from `--var key=value` and override the file's `vars`.
+    pub fn load(path: &str, cli_vars: &Map<String, Value>) -> Result<Workflow, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read workflow '{}': {}", path, e))?;
+        let doc = parse_document(path, &content)?;
+        let base_dir = Path::new(path).parent().unwrap_or(Path::new("."));
+
+        // A bare list of steps is shorthand for { steps: [...] }
+        let (root, steps) = match doc {
+            Value::Array(steps) => (Map::new(), steps),
+            Value::Object(mut root) => {
+                let steps = match root.remove("steps") {
+                    Some(Value::Array(steps)) => steps,
+                    _ => return Err(format!("{}: 'steps' must be a list", path)),
+                };
+                (root, steps)
+            }
+            _ => return Err(format!("{}: expected a list of steps or a mapping", path)),
+        };
+
+        for key in root.keys() {
+            if !matches!(key.as_str(), "name" | "vars" | "timeout" | "retries") {
+                return Err(format!("{}: unknown key '{}'", path, key));
+            }
+        }
+
+        // Vars may reference env and earlier vars; --var values are taken literally
+        let mut vars = Map::new();
+        match root.get("vars") {
+            Some(Value::Object(file_vars)) => {
+                for (key, value) in file_vars {
+                    let value = match value {
+                        Value::String(s) => Value::String(
+                            interpolate(s, &vars).map_err(|e| format!("vars.{}: {}", key, e))?,
+                        ),
+                        other => other.clone(),
+                    };
+                    vars.insert(key.clone(), value);
+                }
+            }
+            Some(_) => return Err(format!("{}: 'vars' must be a mapping", path)),
+            None => {}
+        }
+        for (key, value) in cli_vars {
+            vars.insert(key.clone(), value.clone());
+        }
+
+        Ok(Workflow {
+            name: root.get("name").and_then(|v| v.as_str()).map(String::from),
+            source: path.to_string(),
+            vars,
+            steps: parse_steps(&steps, base_dir, "steps")?,
+            timeout_ms: parse_u64(root.get("timeout"), "timeout")?,
+            retries: parse_u64(root.get("retries"), "retries")?.unwrap_or(0) as u32,
+        })
+    }
+
+    /// Commands a run would send, in order, for --dry-run. Loops are
+    /// expanded; `if` conditions need a browser, so guarded steps are
+    /// included, and values saved with --save-as stay `${name}` placeholders.
+    pub fn dry_run(&self, flags: &Flags) -> Result<Vec<Value>, String> {
+        let mut scope = vars::load(&flags.session);
+        scope.extend(self.vars.clone());
+        let mut out = Vec::new();
+        collect_commands(self, &self.steps, &mut scope, flags, &mut out)?;
+        Ok(out)
+    }
+
+    /// Timeout of a step: its own, else the workflow's, else
+    /// `--default-timeout`, else 30 seconds.
+    fn step_timeout_ms(&self, step: &Step, flags: &Flags) -> u64 {
+        step.timeout_ms
+            .or(self.timeout_ms)
+            .or(flags.default_timeout)
+            .unwrap_or(DEFAULT_STEP_TIMEOUT_MS)
+    }
+
+    /// Build a workflow from the command lines of a multi-step alias. The
+    /// arguments are final, so `${` is kept literally rather than interpolated.
+    pub fn from_alias(name: &str, lines: Vec<Vec<String>>) -> Workflow {
+        let steps = lines
+            .into_iter()
+            .map(|tokens| Step {
+                name: None,
+                kind: StepKind::Command(tokens.iter().map(|t| t.replace("${", "$${")).collect()),
+                condition: None,
+                timeout_ms: None,
+                retries: None,
+                continue_on_error: false,
+            })
+            .collect();
+        Workflow {
+            name: Some(name.to_string()),
+            source: format!("alias {}", name),
+            vars: Map::new(),
+            steps,
+            timeout_ms: None,
+            retries: 0,
+        }
+    }
+}
+
+fn parse_document(path: &str, content: &str) -> Result<Value, String> {
+    if path.ends_with(".json") {
+        serde_json::from_str(content).map_err(|e| format!("Invalid JSON in '{}': {}", path, e))
+    } else {
+        yaml::parse(content).map_err(|e| format!("Invalid YAML in '{}': {}", path, e))
+    }
+}
+
+fn parse_u64(value: Option<&Value>, ctx: &str) -> Result<Option<u64>, String> {
+    match value {
+        None => Ok(None),
+        Some(v) => v
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("{}: expected a non-negative integer", ctx)),
+    }
+}
+
+fn parse_steps(steps: &[Value], base_dir: &Path, ctx: &str) -> Result<Vec<Step>, String> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, s)| parse_step(s, base_dir, &format!("{}[{}]", ctx, i + 1)))
+        .collect()
+}
+
+fn parse_step(value: &Value, base_dir: &Path, ctx: &str) -> Result<Step, String> {
+    let map = match value {
+        Value::String(line) => {
+            return Ok(Step {
+                name: None,
+                kind: StepKind::Command(tokenize(line).map_err(|e| format!("{}: {}", ctx, e))?),
+                condition: None,
+                timeout_ms: None,
+                retries: None,
+                continue_on_error: false,
+            })
+        }
+        Value::Object(map) => map,
+        _ => return Err(format!("{}: expected a command string or a mapping", ctx)),
+    };
+
+    for key in map.keys() {
+        if !matches!(
+            key.as_str(),
+            "name" | "command" | "if" | "timeout" | "retries" | "continueOnError" | "forEach"
+                | "as" | "steps"
+        ) {
+            return Err(format!("{}: unknown key '{}'", ctx, key));
+        }
+    }
+
+    let kind = match (map.get("command"), map.get("forEach")) {
+        (Some(cmd), None) => StepKind::Command(match cmd {
+            Value::String(line) => tokenize(line).map_err(|e| format!("{}: {}", ctx, e))?,
+            Value::Array(parts) => parts
+                .iter()
+                .map(|p| match p {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect(),
+            _ => return Err(format!("{}: 'command' must be a string or a list", ctx)),
+        }),
+        (None, Some(rows)) => {
+            let steps = match map.get("steps") {
+                Some(Value::Array(steps)) => parse_steps(steps, base_dir, &format!("{}.steps", ctx))?,
+                _ => return Err(format!("{}: 'forEach' requires a 'steps' list", ctx)),
+            };
+            StepKind::Loop {
+                rows: parse_rows(rows, base_dir).map_err(|e| format!("{}: {}", ctx, e))?,
+                as_name: map
+                    .get("as")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("row")
+                    .to_string(),
+                steps,
+            }
+        }
+        (Some(_), Some(_)) => {
+            return Err(format!("{}: use either 'command' or 'forEach', not both", ctx))
+        }
+        (None, None) => return Err(format!("{}: missing 'command' or 'forEach'", ctx)),
+    };
+
+    Ok(Step {
+        name: map.get("name").and_then(|v| v.as_str()).map(String::from),
+        kind,
+        condition: map.get("if").and_then(|v| v.as_str()).map(String::from),
+        timeout_ms: parse_u64(map.get("timeout"), &format!("{}.timeout", ctx))?,
+        retries: parse_u64(map.get("retries"), &format!("{}.retries", ctx))?.map(|r| r as u32),
+        continue_on_error: map
+            .get("continueOnError")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+/// Loop rows: an inline list, a `${var}` reference, or a data file
+/// (`.json`, `.csv`, or YAML) relative to the workflow file.
+fn parse_rows(value: &Value, base_dir: &Path) -> Result<Rows, String> {
+    match value {
+        Value::Array(rows) => Ok(Rows::Inline(rows.clone())),
+        Value::String(s) if s.starts_with("${") && s.ends_with('}') => {
+            Ok(Rows::Var(s[2..s.len() - 1].trim().to_string()))
+        }
+        Value::String(file) => {
+            let path: PathBuf = base_dir.join(file);
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Cannot read data file '{}': {}", path.display(), e))?;
+            let display = path.display().to_string();
+            let rows = if display.ends_with(".csv") {
+                parse_csv(&content)
+            } else {
+                parse_document(&display, &content)?
+            };
+            match rows {
+                Value::Array(rows) => Ok(Rows::Inline(rows)),
+                _ => Err(format!("Data file '{}' must contain a list of rows", display)),
+            }
+        }
+        _ => Err("'forEach' must be a list, a ${var} reference, or a data file".to_string()),
+    }
+}
+
+/// Parse CSV with a header row into a list of objects with string values.
+fn parse_csv(content: &str) -> Value {
+    fn split_row(line: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+                _ => field.push(c),
+            }
+        }
+        fields.push(field);
+        fields
+    }
+
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let Some(header) = lines.next().map(split_row) else {
+        return json!([]);
+    };
+    let rows: Vec<Value> = lines
+        .map(|line| {
+            let row: Map<String, Value> = header
+                .iter()
+                .zip(split_row(line))
+                .map(|(k, v)| (k.trim().to_string(), Value::String(v)))
+                .collect();
+            Value::Object(row)
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+/// Split a command line into arguments, honoring single/double quotes and
+/// backslash escapes (outside single quotes).
+pub fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                current.push(chars.next().ok_or("trailing backslash")?);
+                in_token = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                in_token = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("unterminated quote in: {}", line));
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    if tokens.is_empty() {
+        return Err("empty command".to_string());
+    }
+    Ok(tokens)
+}
+
+/// Replace `${name}`, `${name.field}` and `${env.NAME}` references.
+/// `$${` produces a literal `${`.
+fn interpolate(s: &str, scope: &Map<String, Value>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated '${{' in: {}", s))?;
+        let expr = rest[start + 2..start + end].trim();
+        out.push_str(&stringify(&lookup(expr, scope)?));
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn lookup(expr: &str, scope: &Map<String, Value>) -> Result<Value, String> {
+    if let Some(name) = expr.strip_prefix("env.") {
+        return env::var(name)
+            .map(Value::String)
+            .map_err(|_| format!("environment variable {} is not set", name));
+    }
+    let mut parts = expr.split('.');
+    let head = parts.next().unwrap_or_default();
+    let mut value = scope
+        .get(head)
+        .ok_or_else(|| format!("undefined variable '{}'", head))?;
+    for part in parts {
+        value = match value {
+            Value::Object(map) => map.get(part),
+            Value::Array(items) => part.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("undefined variable '{}'", expr))?;
+    }
+    Ok(value.clone())
+}
+
+fn stringify(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// A condition holds when its command succeeds and its single result field
+/// (e.g. `visible`, `count`, `text`) is not false, zero or empty.
+fn is_truthy(resp: &Response) -> bool {
+    if !resp.success {
+        return false;
+    }
+    let Some(Value::Object(data)) = &resp.data else {
+        return true;
+    };
+    let mut fields = data.iter().filter(|(k, _)| k.as_str() != "origin");
+    match (fields.next(), fields.next()) {
+        (Some((_, value)), None) => match value {
+            Value::Bool(b) => *b,
+            Value::Number(n) => n.as_f64() != Some(0.0),
+            Value::String(s) => !s.is_empty(),
+            Value::Array(a) => !a.is_empty(),
+            Value::Null => false,
+            Value::Object(_) => true,
+        },
+        _ => true,
+    }
+}
+
+struct Runner<'a> {
+    client: DaemonClient,
+    flags: &'a Flags,
+    workflow: &'a Workflow,
+    output: OutputOptions,
+    report: Option<&'a ReportSpec>,
+    /// No per-step output, e.g. when the summary is JSON or a report goes to stdout
+    quiet: bool,
+    results: Vec<StepResult>,
+    /// Exit code of the failure that stopped the run, if one did
+    aborted: Option<i32>,
+    /// Values captured with `--save-as` during this run; they shadow `vars`
+    saved: Map<String, Value>,
+}
+
+impl Runner<'_> {
+    /// Run steps in order. Returns false if a failure should stop the workflow.
+    fn run_steps(&mut self, steps: &[Step], scope: &Map<String, Value>, suffix: &str) -> bool {
+        for step in steps {
+            if !self.run_step(step, scope, suffix) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn run_step(&mut self, step: &Step, scope: &Map<String, Value>, suffix: &str) -> bool {
+        let mut scope = scope.clone();
+        scope.extend(self.saved.clone());
+        let scope = &scope;
+        let timeout_ms = self.workflow.step_timeout_ms(step, self.flags);
+        let start = Instant::now();
+        let label = format!("{}{}", self.label(step, scope), suffix);
+
+        if let Some(ref condition) = step.condition {
+            match self.check_condition(condition, scope, timeout_ms) {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.results.push(StepResult {
+                        index: self.results.len() + 1,
+                        label,
+                        action: None,
+                        status: StepStatus::Skipped,
+                        duration: start.elapsed(),
+                        attempts: 0,
+                        error: None,
+                        data: None,
+                        attachments: Vec::new(),
+                    });
+                    return true;
+                }
+                Err(e) => {
+                    let error = (format!("if: {}", e), exit_code::USAGE);
+                    return self.fail(step, label, None, start, 0, error);
+                }
+            }
+        }
+
+        match &step.kind {
+            StepKind::Loop {
+                rows,
+                as_name,
+                steps,
+            } => {
+                let rows = match rows {
+                    Rows::Inline(rows) => rows.clone(),
+                    Rows::Var(name) => match lookup(name, scope) {
+                        Ok(Value::Array(rows)) => rows,
+                        Ok(_) => {
+                            let msg = format!("'{}' is not a list", name);
+                            let error = (msg, exit_code::USAGE);
+                            return self.fail(step, label, None, start, 0, error);
+                        }
+                        Err(e) => {
+                            return self.fail(step, label, None, start, 0, (e, exit_code::USAGE))
+                        }
+                    },
+                };
+                for (i, row) in rows.into_iter().enumerate() {
+                    let mut row_scope = scope.clone();
+                    row_scope.insert(as_name.clone(), row);
+                    let suffix = format!("{} [{} {}]", suffix, as_name, i + 1);
+                    if !self.run_steps(steps, &row_scope, &suffix) {
+                        return step.continue_on_error;
+                    }
+                }
+                true
+            }
+            StepKind::Command(tokens) => {
+                let (cmd, save_as) = match build_command(tokens, scope, self.flags) {
+                    Ok(built) => built,
+                    Err(e) => {
+                        return self.fail(step, label, None, start, 0, (e, exit_code::USAGE))
+                    }
+                };
+                let action = cmd.get("action").and_then(|v| v.as_str()).map(String::from);
+                let retries = step.retries.unwrap_or(self.workflow.retries);
+
+                if !self.quiet {
+                    println!("{} {}", color::dim(&format!("[{}]", self.results.len() + 1)), label);
+                }
+
+                let mut attempts = 0;
+                loop {
+                    attempts += 1;
+                    let mut cmd = cmd.clone();
+                    cmd["id"] = json!(gen_id());
+                    let timeout = apply_step_timeout(&mut cmd, timeout_ms);
+                    let error = match self.client.send(&cmd, timeout) {
+                        Ok(resp) if resp.success && !needs_confirmation(&resp) => {
+                            if !self.quiet {
+                                print_response_with_opts(&resp, action.as_deref(), &self.output);
+                            }
+                            if let Some(name) = save_as {
+                                let value =
+                                    resp.data.as_ref().map(vars::result_value).unwrap_or_default();
+                                if let Err(e) = vars::set(&self.flags.session, &name, value.clone())
+                                {
+                                    eprintln!("{} {}", color::warning_indicator(), e);
+                                }
+                                self.saved.insert(name, value);
+                            }
+                            self.results.push(StepResult {
+                                index: self.results.len() + 1,
+                                label,
+                                action,
+                                status: StepStatus::Passed,
+                                duration: start.elapsed(),
+                                attempts,
+                                error: None,
+                                data: resp.data,
+                                attachments: Vec::new(),
+                            });
+                            return true;
+                        }
+                        // Workflows cannot answer a confirmation prompt
+                        Ok(resp) if resp.success => (
+                            "Action requires confirmation".to_string(),
+                            exit_code::POLICY_DENIED,
+                        ),
+                        Ok(resp) => {
+                            let code = exit_code::for_response(&resp);
+                            (resp.error.unwrap_or_else(|| "Unknown error".to_string()), code)
+                        }
+                        Err(e) => {
+                            let code = exit_code::for_send_error(&e);
+                            (e, code)
+                        }
+                    };
+                    if attempts > retries {
+                        return self.fail(step, label, action, start, attempts, error);
+                    }
+                    if !self.quiet {
+                        eprintln!(
+                            "{} {} (retrying, attempt {}/{})",
+                            color::warning_indicator(),
+                            error.0,
+                            attempts + 1,
+                            retries + 1
+                        );
+                    }
+                    thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
+                }
+            }
+        }
+    }
+
+    fn check_condition(
+        &mut self,
+        condition: &str,
+        scope: &Map<String, Value>,
+        timeout_ms: u64,
+    ) -> Result<bool, String> {
+        let (negate, condition) = match condition.trim().strip_prefix("not ") {
+            Some(rest) => (true, rest),
+            None => (false, condition),
+        };
+        let tokens = tokenize(condition)?;
+        let (mut cmd, _) = build_command(&tokens, scope, self.flags)?;
+        cmd["id"] = json!(gen_id());
+        let timeout = apply_step_timeout(&mut cmd, timeout_ms);
+        // A failing check (e.g. element missing) counts as false
+        let holds = match self.client.send(&cmd, timeout) {
+            Ok(resp) => is_truthy(&resp),
+            Err(_) => false,
+        };
+        Ok(holds != negate)
+    }
+
+    fn label(&self, step: &Step, scope: &Map<String, Value>) -> String {
+        if let Some(ref name) = step.name {
+            return interpolate(name, scope).unwrap_or_else(|_| name.clone());
+        }
+        match &step.kind {
+            StepKind::Command(tokens) => tokens
+                .iter()
+                .map(|t| interpolate(t, scope).unwrap_or_else(|_| t.clone()))
+                .collect::<Vec<_>>()
+                .join(" "),
+            StepKind::Loop { as_name, .. } => format!("forEach {}", as_name),
+        }
+    }
+
+    /// Record a failed step. `error` is the message and the exit code the
+    /// run ends with if this failure stops it.
+    fn fail(
+        &mut self,
+        step: &Step,
+        label: String,
+        action: Option<String>,
+        start: Instant,
+        attempts: u32,
+        (error, code): (String, i32),
+    ) -> bool {
+        if !self.quiet {
+            eprintln!("{} {}", color::error_indicator(), error);
+        }
+        let attachments = match (self.report, &action) {
+            (Some(report), Some(_)) => {
+                let client = &mut self.client;
+                report.capture_failure(&label, &mut |cmd| {
+                    client.send(&cmd, Duration::from_secs(10))
+                })
+            }
+            _ => Vec::new(),
+        };
+        self.results.push(StepResult {
+            index: self.results.len() + 1,
+            label,
+            action,
+            status: StepStatus::Failed,
+            duration: start.elapsed(),
+            attempts,
+            error: Some(error),
+            data: None,
+            attachments,
+        });
+        if !step.continue_on_error {
+            self.aborted.get_or_insert(code);
+        }
+        step.continue_on_error
+    }
+}
+
+fn needs_confirmation(resp: &Response) -> bool {
+    resp.data
+        .as_ref()
+        .and_then(|d| d.get("confirmation_required"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn build_command(
+    tokens: &[String],
+    scope: &Map<String, Value>,
+    flags: &Flags,
+) -> Result<(Value, Option<String>), String> {
+    let args = tokens
+        .iter()
+        .map(|t| interpolate(t, scope))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut clean = clean_args(&args);
+    let save_as = vars::take_save_as(&mut clean)?;
+    let cmd = parse_command(&clean, flags).map_err(|e| e.format())?;
+    if let Some(action) = cmd.get("action").and_then(|v| v.as_str()) {
+        if LOCAL_ACTIONS.contains(&action) {
+            return Err(format!("'{}' cannot be used inside a workflow", args[0]));
+        }
+    }
+    Ok((cmd, save_as))
+}
+
+/// Send the step timeout to the daemon unless the command sets its own, and
+/// return how long to wait for the response.
+fn apply_step_timeout(cmd: &mut Value, timeout_ms: u64) -> Duration {
+    if cmd.get("timeout").is_none() {
+        cmd["timeout"] = json!(timeout_ms);
+    }
+    read_timeout(cmd, timeout_ms)
+}
+
+fn collect_commands(
+    workflow: &Workflow,
+    steps: &[Step],
+    scope: &mut Map<String, Value>,
+    flags: &Flags,
+    out: &mut Vec<Value>,
+) -> Result<(), String> {
+    for step in steps {
+        match &step.kind {
+            StepKind::Command(tokens) => {
+                let (mut cmd, save_as) = build_command(tokens, scope, flags)?;
+                apply_step_timeout(&mut cmd, workflow.step_timeout_ms(step, flags));
+                if let Some(name) = save_as {
+                    scope.insert(name.clone(), json!(format!("${{{}}}", name)));
+                }
+                out.push(cmd);
+            }
+            StepKind::Loop {
+                rows,
+                as_name,
+                steps,
+            } => {
+                let rows = match rows {
+                    Rows::Inline(rows) => rows.clone(),
+                    Rows::Var(name) => match lookup(name, scope)? {
+                        Value::Array(rows) => rows,
+                        _ => return Err(format!("'{}' is not a list", name)),
+                    },
+                };
+                for row in rows {
+                    let mut row_scope = scope.clone();
+                    row_scope.insert(as_name.clone(), row);
+                    collect_commands(workflow, steps, &mut row_scope, flags, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run a loaded workflow over a single daemon connection, print a summary
+/// (and write a report if requested), and exit non-zero if a step failed
+/// without `continueOnError`.
+pub fn run_workflow(workflow: &Workflow, flags: &Flags, report: Option<&ReportSpec>) -> ! {
+    let start = Instant::now();
+    let mut runner = Runner {
+        client: DaemonClient::new(&flags.session),
+        flags,
+        workflow,
+        output: OutputOptions {
+            json: flags.json,
+            content_boundaries: flags.content_boundaries,
+            max_output: flags.max_output,
+            max_tokens: flags.max_tokens,
+            offset: None,
+            page: None,
+            field: flags.field.clone(),
+            format: flags.format.clone(),
+            out: None,
+            injection_scan: flags.injection_scan.as_deref().and_then(injection::Mode::parse),
+        },
+        report,
+        quiet: flags.json || report.is_some_and(|r| r.to_stdout()),
+        results: Vec::new(),
+        aborted: None,
+        saved: Map::new(),
+    };
+    // Variables saved by earlier commands in this session are visible too,
+    // unless the workflow defines its own
+    let mut scope = vars::load(&flags.session);
+    scope.extend(workflow.vars.clone());
+    runner.run_steps(&workflow.steps, &scope, "");
+
+    let results = runner.results;
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let (passed, failed, skipped) = (
+        count(StepStatus::Passed),
+        count(StepStatus::Failed),
+        count(StepStatus::Skipped),
+    );
+    let elapsed = start.elapsed();
+    let success = runner.aborted.is_none();
+    // The step that stopped the run decides the exit code, as for a single command
+    let code = runner.aborted.unwrap_or(0);
+
+    if let Some(report) = report {
+        let suite = workflow.name.as_deref().unwrap_or(&workflow.source);
+        let cases: Vec<TestCase> = results.iter().map(TestCase::from).collect();
+        if let Err(e) = report.write(suite, &cases) {
+            eprintln!("{} {}", color::error_indicator(), e);
+        }
+        if report.to_stdout() {
+            exit(code);
+        }
+    }
+
+    if flags.json {
+        let steps: Vec<Value> = results
+            .iter()
+            .map(|r| {
+                json!({
+                    "index": r.index,
+                    "step": r.label,
+                    "action": r.action,
+                    "status": r.status.as_str(),
+                    "durationMs": r.duration.as_millis() as u64,
+                    "attempts": r.attempts,
+                    "error": r.error,
+                    "data": r.data,
+                    "attachments": r.attachments,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({
+                "success": success,
+                "data": {
+                    "name": workflow.name,
+                    "passed": passed,
+                    "failed": failed,
+                    "skipped": skipped,
+                    "durationMs": elapsed.as_millis() as u64,
+                    "steps": steps,
+                },
+                "error": if success { Value::Null } else { json!("Workflow failed") },
+            })
+        );
+    } else {
+        println!();
+        println!(
+            "{}",
+            color::bold(workflow.name.as_deref().unwrap_or("Workflow summary"))
+        );
+        for r in &results {
+            let (indicator, detail) = match r.status {
+                StepStatus::Passed => (
+                    color::success_indicator().to_string(),
+                    format!("{}ms", r.duration.as_millis()),
+                ),
+                StepStatus::Skipped => ("-".to_string(), "skipped".to_string()),
+                StepStatus::Failed => (
+                    color::error_indicator().to_string(),
+                    format!(
+                        "{}ms, {} attempt{}: {}",
+                        r.duration.as_millis(),
+                        r.attempts,
+                        if r.attempts == 1 { "" } else { "s" },
+                        r.error.as_deref().unwrap_or("failed")
+                    ),
+                ),
+            };
+            println!(
+                "  {} [{}] {} {}",
+                indicator,
+                r.index,
+                r.label,
+                color::dim(&format!("({})", detail))
+            );
+        }
+        println!(
+            "{} passed, {} failed, {} skipped in {:.2}s",
+            passed,
+            failed,
+            skipped,
+            elapsed.as_secs_f64()
+        );
+    }
+
+    exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::parse_flags;
+
+    fn scope() -> Map<String, Value> {
+        json!({
+            "base": "https://example.com",
+            "user": { "name": "alice", "roles": ["admin"] },
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_tokenize_quotes() {
+        assert_eq!(
+            tokenize(r#"fill @e1 "hello world" 'it"s' a\ b"#).unwrap(),
+            vec!["fill", "@e1", "hello world", "it\"s", "a b"]
+        );
+        assert_eq!(tokenize(r#"fill @e1 """#).unwrap(), vec!["fill", "@e1", ""]);
+        assert!(tokenize("click \"@e1").is_err());
+    }
+
+    #[test]
+    fn test_interpolate_vars_and_fields() {
+        let s = scope();
+        assert_eq!(
+            interpolate("${base}/u/${user.name}/${user.roles.0}", &s).unwrap(),
+            "https://example.com/u/alice/admin"
+        );
+        assert_eq!(interpolate("cost $${price}", &s).unwrap(), "cost ${price}");
+        assert!(interpolate("${missing}", &s).unwrap_err().contains("undefined variable"));
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        env::set_var("AGENT_BROWSER_TEST_WORKFLOW_USER", "bob");
+        assert_eq!(
+            interpolate("${env.AGENT_BROWSER_TEST_WORKFLOW_USER}", &Map::new()).unwrap(),
+            "bob"
+        );
+        assert!(interpolate("${env.AGENT_BROWSER_TEST_UNSET_VAR}", &Map::new())
+            .unwrap_err()
+            .contains("is not set"));
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        assert_eq!(
+            parse_csv("id,name\n1,\"Smith, J\"\n2,\"say \"\"hi\"\"\"\n"),
+            json!([
+                { "id": "1", "name": "Smith, J" },
+                { "id": "2", "name": "say \"hi\"" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        let resp = |data: Value| Response {
+            success: true,
+            data: Some(data),
+            ..Default::default()
+        };
+        assert!(is_truthy(&resp(json!({ "visible": true }))));
+        assert!(!is_truthy(&resp(json!({ "visible": false }))));
+        assert!(!is_truthy(&resp(json!({ "count": 0 }))));
+        assert!(is_truthy(&resp(json!({ "text": "hi", "origin": "https://a.test" }))));
+        assert!(!is_truthy(&Response {
+            success: false,
+            data: None,
+            error: Some("Element not found".to_string()),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_load_workflow() {
+        let path = env::temp_dir().join("agent-browser-test-workflow.yaml");
+        fs::write(
+            &path,
+            "name: Login\nvars:\n  base: https://example.com\n  login: ${base}/login\nretries: 1\nsteps:\n  - open ${login}\n  - command: click @login\n    if: is visible @login\n    timeout: 5000\n  - forEach: [{q: a}, {q: b}]\n    as: item\n    steps:\n      - fill @search ${item.q}\n",
+        )
+        .unwrap();
+        let wf = Workflow::load(path.to_str().unwrap(), &Map::new()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(wf.name.as_deref(), Some("Login"));
+        assert_eq!(wf.vars["login"], "https://example.com/login");
+        assert_eq!(wf.retries, 1);
+        assert_eq!(wf.steps.len(), 3);
+        assert_eq!(wf.steps[1].condition.as_deref(), Some("is visible @login"));
+        assert_eq!(wf.steps[1].timeout_ms, Some(5000));
+        assert!(matches!(
+            &wf.steps[2].kind,
+            StepKind::Loop { as_name, steps, .. } if as_name == "item" && steps.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_dry_run_expands_loops_and_keeps_saved_placeholders() {
+        let path = env::temp_dir().join("agent-browser-test-dry-run.yaml");
+        fs::write(
+            &path,
+            "vars:\n  base: https://example.com\nsteps:\n  - open ${base}\n  - get text @h1 --save-as heading\n  - forEach: [a, b]\n    as: q\n    steps:\n      - fill @search \"${q} ${heading}\"\n",
+        )
+        .unwrap();
+        let wf = Workflow::load(path.to_str().unwrap(), &Map::new()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let cmds = wf.dry_run(&parse_flags(&[])).unwrap();
+        let actions: Vec<&str> = cmds.iter().map(|c| c["action"].as_str().unwrap()).collect();
+        assert_eq!(actions, vec!["navigate", "gettext", "fill", "fill"]);
+        assert_eq!(cmds[0]["url"], "https://example.com");
+        assert_eq!(cmds[2]["value"], "a ${heading}");
+        assert_eq!(cmds[3]["value"], "b ${heading}");
+    }
+
+    #[test]
+    fn test_step_timeout_is_sent_to_daemon() {
+        let path = env::temp_dir().join("agent-browser-test-step-timeout.yaml");
+        fs::write(
+            &path,
+            "timeout: 10000\nsteps:\n  - snapshot\n  - command: wait 45000\n  - command: click @e1\n    timeout: 2000\n",
+        )
+        .unwrap();
+        let wf = Workflow::load(path.to_str().unwrap(), &Map::new()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let cmds = wf.dry_run(&parse_flags(&[])).unwrap();
+        assert_eq!(cmds[0]["timeout"], 10000);
+        assert_eq!(cmds[1]["timeout"], 45000);
+        assert_eq!(cmds[2]["timeout"], 2000);
+
+        let mut wait = cmds[1].clone();
+        assert_eq!(apply_step_timeout(&mut wait, 10000), Duration::from_millis(50_000));
+        let defaults = Workflow::from_alias("a", vec![vec!["snapshot".to_string()]]);
+        let args = ["--default-timeout".to_string(), "60000".to_string()];
+        let cmds = defaults.dry_run(&parse_flags(&args)).unwrap();
+        assert_eq!(cmds[0]["timeout"], 60000);
+    }
+
+    #[test]
+    fn test_load_workflow_rejects_unknown_keys() {
+        let path = env::temp_dir().join("agent-browser-test-workflow-bad.json");
+        fs::write(&path, r#"{"steps": [{"command": "snapshot", "retry": 2}]}"#).unwrap();
+        let err = Workflow::load(path.to_str().unwrap(), &Map::new())
+            .err()
+            .unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(err.contains("steps[1]: unknown key 'retry'"), "{}", err);
+    }
+
+    #[test]
+    fn test_cli_vars_override_file_vars() {
+        let path = env::temp_dir().join("agent-browser-test-workflow-vars.json");
+        fs::write(&path, r#"{"vars": {"user": "alice"}, "steps": ["open ${user}"]}"#).unwrap();
+        let cli_vars = json!({ "user": "bob" }).as_object().unwrap().clone();
+        let wf = Workflow::load(path.to_str().unwrap(), &cli_vars).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(wf.vars["user"], "bob");
+    }
+}
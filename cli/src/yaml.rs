@@ -0,0 +1,501 @@
+//! Minimal YAML reader for workflow files.
+//!
+//! Supports the subset workflows need: block mappings and sequences, flow
+//! collections (`[a, b]`, `{k: v}`), plain/single/double-quoted scalars,
+//! `|` and `>` block scalars, and `#` comments. Anchors, tags and multi-line
+//! plain scalars are not supported. Mapping keys cannot contain whitespace
+//! unless quoted, so steps like `- wait --text "Done: ok"` stay plain strings.
+
+use serde_json::{Map, Number, Value};
+
+struct Line {
+    num: usize,
+    indent: usize,
+    text: String,
+}
+
+struct Parser {
+    raw: Vec<String>,
+    lines: Vec<Option<Line>>,
+    pos: usize,
+}
+
+pub fn parse(src: &str) -> Result<Value, String> {
+    let raw: Vec<String> = src.lines().map(|l| l.trim_end().to_string()).collect();
+    let lines = raw
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            let text = strip_comment(l.trim_start());
+            if text.is_empty() || text == "---" || text == "..." {
+                return None;
+            }
+            Some(Line {
+                num: i + 1,
+                indent: l.len() - l.trim_start().len(),
+                text: text.to_string(),
+            })
+        })
+        .collect();
+    let mut parser = Parser { raw, lines, pos: 0 };
+    let Some(indent) = parser.peek().map(|l| l.indent) else {
+        return Ok(Value::Null);
+    };
+    let value = parser.parse_block(indent)?;
+    if let Some(line) = parser.peek() {
+        return Err(format!("line {}: unexpected indentation", line.num));
+    }
+    Ok(value)
+}
+
+impl Parser {
+    fn peek(&mut self) -> Option<&Line> {
+        while self.pos < self.lines.len() && self.lines[self.pos].is_none() {
+            self.pos += 1;
+        }
+        self.lines.get(self.pos).and_then(|l| l.as_ref())
+    }
+
+    fn parse_block(&mut self, indent: usize) -> Result<Value, String> {
+        match self.peek() {
+            Some(line) if is_seq_item(&line.text) => self.parse_sequence(indent),
+            Some(_) => self.parse_mapping(indent),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Value, String> {
+        let mut items = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent != indent || !is_seq_item(&line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start().to_string();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_nested(indent)?);
+                continue;
+            }
+            if !is_seq_item(&rest) && split_key_value(&rest).is_none() {
+                let num = line.num;
+                self.pos += 1;
+                let value = if rest.starts_with('|') || rest.starts_with('>') {
+                    self.parse_block_scalar(&rest, indent, num)?
+                } else {
+                    parse_inline(&rest).map_err(|e| format!("line {}: {}", num, e))?
+                };
+                items.push(value);
+                continue;
+            }
+            // Re-read the item content as a line of its own, indented to where
+            // it starts, so "- key: value" opens a mapping at that column.
+            let offset = line.text.len() - rest.len();
+            let line = self.lines[self.pos].as_mut().expect("peeked line exists");
+            line.indent += offset;
+            line.text = rest;
+            let item_indent = line.indent;
+            items.push(self.parse_block(item_indent)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        while let Some(line) = self.peek() {
+            if line.indent != indent || is_seq_item(&line.text) {
+                break;
+            }
+            let num = line.num;
+            let (key, value) = split_key_value(&line.text)
+                .ok_or_else(|| format!("line {}: expected 'key: value'", num))?;
+            self.pos += 1;
+            if map.contains_key(&key) {
+                return Err(format!("line {}: duplicate key '{}'", num, key));
+            }
+            let value = match value.as_str() {
+                "" => match self.peek() {
+                    // YAML allows a sequence at the same indent as its key
+                    Some(next) if next.indent == indent && is_seq_item(&next.text) => {
+                        self.parse_sequence(indent)?
+                    }
+                    _ => self.parse_nested(indent)?,
+                },
+                v if v.starts_with('|') || v.starts_with('>') => {
+                    self.parse_block_scalar(v, indent, num)?
+                }
+                v => parse_inline(v).map_err(|e| format!("line {}: {}", num, e))?,
+            };
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// Parse the block nested under a key or bare `-`, or null if there is none.
+    fn parse_nested(&mut self, parent_indent: usize) -> Result<Value, String> {
+        match self.peek().map(|l| l.indent) {
+            Some(child) if child > parent_indent => self.parse_block(child),
+            _ => Ok(Value::Null),
+        }
+    }
+
+    fn parse_block_scalar(
+        &mut self,
+        header: &str,
+        parent_indent: usize,
+        num: usize,
+    ) -> Result<Value, String> {
+        let folded = header.starts_with('>');
+        let chomp = &header[1..];
+        if !matches!(chomp, "" | "-" | "+") {
+            return Err(format!("line {}: unsupported block scalar header '{}'", num, header));
+        }
+
+        // Block scalars keep comments and blank lines, so read the raw source
+        let mut body: Vec<&str> = Vec::new();
+        let mut content_indent = None;
+        let mut end = num; // index of the first raw line after the scalar
+        for (i, raw) in self.raw.iter().enumerate().skip(num) {
+            let trimmed = raw.trim_start();
+            let indent = raw.len() - trimmed.len();
+            if trimmed.is_empty() {
+                body.push("");
+                end = i + 1;
+                continue;
+            }
+            if !raw[..indent].is_ascii() {
+                return Err(format!("line {}: indentation must use spaces", i + 1));
+            }
+            if indent <= parent_indent {
+                break;
+            }
+            let ci = *content_indent.get_or_insert(indent);
+            if indent < ci {
+                break;
+            }
+            body.push(&raw[ci..]);
+            end = i + 1;
+        }
+        while body.last() == Some(&"") && end > num {
+            body.pop();
+            end -= 1;
+        }
+        self.pos = self.pos.max(end);
+
+        let mut text = if folded {
+            let mut out = String::new();
+            for (i, l) in body.iter().enumerate() {
+                if i > 0 {
+                    out.push(if l.is_empty() || body[i - 1].is_empty() { '\n' } else { ' ' });
+                }
+                out.push_str(l);
+            }
+            out
+        } else {
+            body.join("\n")
+        };
+        if chomp != "-" && !text.is_empty() {
+            text.push('\n');
+        }
+        Ok(Value::String(text))
+    }
+}
+
+fn is_seq_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Remove a trailing `# comment` that is outside quotes.
+fn strip_comment(s: &str) -> &str {
+    let mut quote = None;
+    let mut prev_space = true;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && prev_space => quote = Some(c),
+            None if c == '#' && prev_space => return s[..i].trim_end(),
+            None => {}
+        }
+        prev_space = c.is_whitespace();
+    }
+    s
+}
+
+/// Split `key: value` (or `key:`). Returns None if the line is not a mapping entry.
+fn split_key_value(text: &str) -> Option<(String, String)> {
+    if text.starts_with('"') || text.starts_with('\'') {
+        let (key, len) = parse_quoted(text).ok()?;
+        let rest = text[len..].strip_prefix(':')?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        return Some((key, rest.trim().to_string()));
+    }
+    let idx = text.find(':')?;
+    let key = &text[..idx];
+    let rest = &text[idx + 1..];
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((key.to_string(), rest.trim().to_string()))
+}
+
+fn parse_inline(s: &str) -> Result<Value, String> {
+    if s.starts_with('[') || s.starts_with('{') {
+        let mut flow = Flow { s, pos: 0 };
+        let value = flow.value()?;
+        flow.skip_ws();
+        if flow.pos < s.len() {
+            return Err(format!("unexpected '{}' after flow collection", &s[flow.pos..]));
+        }
+        return Ok(value);
+    }
+    if s.starts_with('"') || s.starts_with('\'') {
+        let (value, len) = parse_quoted(s)?;
+        if !s[len..].trim().is_empty() {
+            return Err(format!("unexpected text after quoted string: {}", &s[len..]));
+        }
+        return Ok(Value::String(value));
+    }
+    Ok(plain_scalar(s))
+}
+
+/// Parse a quoted string at the start of `s`. Returns the value and the
+/// number of bytes consumed, including both quotes.
+fn parse_quoted(s: &str) -> Result<(String, usize), String> {
+    let quote = s.chars().next().ok_or("empty string")?;
+    let mut out = String::new();
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            // '' is an escaped quote inside single-quoted strings
+            if quote == '\'' && chars.peek().map(|&(_, n)| n) == Some('\'') {
+                chars.next();
+                out.push('\'');
+                continue;
+            }
+            return Ok((out, i + 1));
+        }
+        if c == '\\' && quote == '"' {
+            let (_, esc) = chars.next().ok_or("unterminated escape")?;
+            match esc {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                '0' => out.push('\0'),
+                '"' | '\\' | '/' => out.push(esc),
+                'u' => {
+                    let hex: String = (0..4).filter_map(|_| chars.next().map(|(_, h)| h)).collect();
+                    let code = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| format!("invalid escape \\u{}", hex))?;
+                    out.push(code);
+                }
+                other => return Err(format!("invalid escape \\{}", other)),
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    Err(format!("unterminated string: {}", s))
+}
+
+fn plain_scalar(s: &str) -> Value {
+    match s {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(n) = s.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    let numeric = s.chars().any(|c| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+    if numeric {
+        if let Some(n) = s.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(s.to_string())
+}
+
+struct Flow<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Flow<'_> {
+    fn skip_ws(&mut self) {
+        // Whitespace may be multi-byte (U+3000), so skip by byte length
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.s[self.pos..].starts_with(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        if rest.starts_with('[') {
+            self.pos += 1;
+            let mut items = Vec::new();
+            while !self.eat(']') {
+                items.push(self.value()?);
+                if !self.eat(',') && !self.s[self.pos..].trim_start().starts_with(']') {
+                    return Err(format!("expected ',' or ']' in {}", self.s));
+                }
+            }
+            return Ok(Value::Array(items));
+        }
+        if rest.starts_with('{') {
+            self.pos += 1;
+            let mut map = Map::new();
+            while !self.eat('}') {
+                let key = match self.scalar(true)? {
+                    Value::String(k) => k,
+                    other => other.to_string(),
+                };
+                if !self.eat(':') {
+                    return Err(format!("expected ':' after key '{}' in {}", key, self.s));
+                }
+                let value = self.value()?;
+                map.insert(key, value);
+                if !self.eat(',') && !self.s[self.pos..].trim_start().starts_with('}') {
+                    return Err(format!("expected ',' or '}}' in {}", self.s));
+                }
+            }
+            return Ok(Value::Object(map));
+        }
+        self.scalar(false)
+    }
+
+    fn scalar(&mut self, is_key: bool) -> Result<Value, String> {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        if rest.starts_with('"') || rest.starts_with('\'') {
+            let (value, len) = parse_quoted(rest)?;
+            self.pos += len;
+            return Ok(Value::String(value));
+        }
+        let stops: &[char] = if is_key { &[',', ']', '}', ':'] } else { &[',', ']', '}'] };
+        let len = rest.find(stops).unwrap_or(rest.len());
+        self.pos += len;
+        Ok(plain_scalar(rest[..len].trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mapping_and_sequence() {
+        let src = "name: Login\nvars:\n  user: alice\n  retries: 3\nsteps:\n  - open https://example.com\n  - click @e1\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({
+                "name": "Login",
+                "vars": { "user": "alice", "retries": 3 },
+                "steps": ["open https://example.com", "click @e1"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_mapping_inside_sequence() {
+        let src = "steps:\n- command: click @login\n  if: is visible @login\n  retries: 2\n- snapshot\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({
+                "steps": [
+                    { "command": "click @login", "if": "is visible @login", "retries": 2 },
+                    "snapshot"
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_quotes_and_comments() {
+        let src = "# header\na: \"x # not a comment\"  # comment\nb: 'it''s'\nc: \"tab\\there\"\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({ "a": "x # not a comment", "b": "it's", "c": "tab\there" })
+        );
+    }
+
+    #[test]
+    fn test_colon_in_step_is_not_a_key() {
+        let src = "- wait --text \"Done: ok\"\n- open http://localhost:3000\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!(["wait --text \"Done: ok\"", "open http://localhost:3000"])
+        );
+    }
+
+    #[test]
+    fn test_flow_collections() {
+        let src = "rows: [{id: 1, name: a}, {id: 2, name: \"b, c\"}]\nempty: []\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({
+                "rows": [{ "id": 1, "name": "a" }, { "id": 2, "name": "b, c" }],
+                "empty": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_flow_multibyte_whitespace() {
+        let src = "forEach: [a,\u{3000}b]\nmap: {\u{3000}k:\u{2003}v}\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({ "forEach": ["a", "b"], "map": { "k": "v" } })
+        );
+    }
+
+    #[test]
+    fn test_block_scalar_multibyte_indent() {
+        let err = parse("script: |\n  a\n\u{3000}b\n").unwrap_err();
+        assert!(err.contains("line 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_block_scalars() {
+        let src = "script: |\n  const a = 1;\n\n  return a;\nnote: >-\n  one\n  two\nnext: x\n";
+        assert_eq!(
+            parse(src).unwrap(),
+            json!({ "script": "const a = 1;\n\nreturn a;\n", "note": "one two", "next": "x" })
+        );
+    }
+
+    #[test]
+    fn test_scalar_types() {
+        assert_eq!(
+            parse("a: true\nb: ~\nc: 1.5\nd: 007x\n").unwrap(),
+            json!({ "a": true, "b": null, "c": 1.5, "d": "007x" })
+        );
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        let err = parse("a: 1\na: 2\n").unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+        let err = parse("a: \"open\n").unwrap_err();
+        assert!(err.contains("line 1"), "{}", err);
+    }
+}
@@ -70,6 +70,7 @@ const ACTION_CATEGORIES: Record<string, string> = {
   title: 'get',
   getattribute: 'get',
   count: 'get',
+  assert: 'get',
   boundingbox: 'get',
   styles: 'get',
   isvisible: 'get',
@@ -80,6 +81,7 @@ const ACTION_CATEGORIES: Record<string, string> = {
   route: 'network',
   unroute: 'network',
   requests: 'network',
+  requests_export: 'network',
 
   state_save: 'state',
   state_load: 'state',
@@ -125,6 +127,7 @@ const ACTION_CATEGORIES: Record<string, string> = {
   bringtofront: '_internal',
   trace_start: '_internal',
   trace_stop: '_internal',
+  trace_chunk: '_internal',
   har_start: '_internal',
   har_stop: '_internal',
   video_start: '_internal',
@@ -142,6 +145,11 @@ const ACTION_CATEGORIES: Record<string, string> = {
   permissions: '_internal',
   emulatemedia: '_internal',
   offline: '_internal',
+  network_conditions: '_internal',
+  proxy: '_internal',
+  cpu_throttle: '_internal',
+  timeout: '_internal',
+  last_output: '_internal',
   headers: '_internal',
   addstyle: 'eval',
   expose: 'eval',
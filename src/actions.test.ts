@@ -36,4 +36,34 @@ describe('toAIFriendlyError', () => {
       expect(result.message).toContain('cookie banners');
     });
   });
+
+  describe('element not found', () => {
+    it('should report a locator that never resolved as not found', () => {
+      const error = new Error(
+        'TimeoutError: locator.click: Timeout 10000ms exceeded.\n' +
+          'Call log:\n' +
+          "  - waiting for locator('#missing')"
+      );
+
+      const result = toAIFriendlyError(error, '#missing');
+
+      expect(result.message).toContain('not found');
+    });
+
+    it('should report a resolved element that never became actionable as a timeout', () => {
+      const error = new Error(
+        'TimeoutError: locator.click: Timeout 10000ms exceeded.\n' +
+          'Call log:\n' +
+          "  - waiting for locator('#slow')\n" +
+          '    - locator resolved to <button id="slow">Go</button>\n' +
+          '  - attempting click action'
+      );
+      error.name = 'TimeoutError';
+
+      const result = toAIFriendlyError(error, '#slow');
+
+      expect(result.message).toContain('timed out');
+      expect(result.name).toBe('TimeoutError');
+    });
+  });
 });
@@ -61,6 +61,7 @@ import type {
   PdfCommand,
   RouteCommand,
   RequestsCommand,
+  RequestsExportCommand,
   DownloadCommand,
   GeolocationCommand,
   PermissionsCommand,
@@ -72,10 +73,12 @@ import type {
   IsEnabledCommand,
   IsCheckedCommand,
   CountCommand,
+  AssertCommand,
   BoundingBoxCommand,
   StylesCommand,
   TraceStartCommand,
   TraceStopCommand,
+  TraceChunkCommand,
   ProfilerStartCommand,
   ProfilerStopCommand,
   HarStopCommand,
@@ -103,6 +106,11 @@ import type {
   AddStyleCommand,
   EmulateMediaCommand,
   OfflineCommand,
+  NetworkConditionsCommand,
+  ProxyCommand,
+  CpuThrottleCommand,
+  TimeoutCommand,
+  LastOutputCommand,
   HeadersCommand,
   GetByAltTextCommand,
   GetByTitleCommand,
@@ -160,9 +168,12 @@ import type {
   InputEventData,
   StylesData,
 } from './types.js';
-import { successResponse, errorResponse, parseCommand } from './protocol.js';
+import { successResponse, errorResponse, errorCodeFor, parseCommand } from './protocol.js';
 import { diffSnapshots, diffScreenshots } from './diff.js';
-import { getEnhancedSnapshot } from './snapshot.js';
+import { findHiddenText, getEnhancedSnapshot } from './snapshot.js';
+import { exportRequest } from './request-export.js';
+import { resolveNetworkConditions } from './network-emulation.js';
+import { describeExpected, matchCount, matchText, parsePattern } from './assertions.js';
 
 // Callback for screencast frames - will be set by the daemon when streaming is active
 let screencastFrameCallback: ((frame: ScreencastFrame) => void) | null = null;
@@ -219,17 +230,11 @@ export function toAIFriendlyError(error: unknown, selector: string): Error {
     );
   }
 
-  // Handle general timeout (element exists but action couldn't complete)
-  if (message.includes('Timeout') && message.includes('exceeded')) {
-    return new Error(
-      `Action on "${selector}" timed out. The element may be blocked, still loading, or not interactable. ` +
-        `Run 'snapshot' to check the current page state.`
-    );
-  }
-
-  // Handle element not found (timeout waiting for element)
+  // Handle element not found (timeout waiting for an element that never
+  // matched; Playwright logs "locator resolved to" once it does)
   if (
     message.includes('waiting for') &&
+    !message.includes('resolved to') &&
     (message.includes('to be visible') || message.includes('Timeout'))
   ) {
     return new Error(
@@ -238,6 +243,16 @@ export function toAIFriendlyError(error: unknown, selector: string): Error {
     );
   }
 
+  // Handle general timeout (element exists but action couldn't complete)
+  if (message.includes('Timeout') && message.includes('exceeded')) {
+    const timeout = new Error(
+      `Action on "${selector}" timed out. The element may be blocked, still loading, or not interactable. ` +
+        `Run 'snapshot' to check the current page state.`
+    );
+    if (error instanceof Error) timeout.name = error.name;
+    return timeout;
+  }
+
   // Return original error for unknown cases
   return error instanceof Error ? error : new Error(message);
 }
@@ -313,13 +328,31 @@ export async function executeCommand(command: Command, browser: BrowserManager):
       });
     }
 
-    return await dispatchAction(command, browser);
+    const response = await dispatchAction(command, browser);
+    if (response.success && CACHED_OUTPUT_ACTIONS.has(command.action)) {
+      browser.setLastOutput(command.action, response.data);
+    }
+    return response;
   } catch (error) {
     const message = error instanceof Error ? error.message : String(error);
-    return errorResponse(command.id, message);
+    return errorResponse(command.id, message, undefined, errorCodeFor(error));
   }
 }
 
+/**
+ * Actions whose output the CLI may truncate; the latest result is kept for
+ * `more` to page through.
+ */
+const CACHED_OUTPUT_ACTIONS = new Set([
+  'snapshot',
+  'evaluate',
+  'content',
+  'gettext',
+  'innertext',
+  'innerhtml',
+  'console',
+]);
+
 /**
  * Dispatch a command to its handler after policy checks have passed.
  */
@@ -411,6 +444,8 @@ async function dispatchAction(command: Command, browser: BrowserManager): Promis
       return await handleUnroute(command, browser);
     case 'requests':
       return await handleRequests(command, browser);
+    case 'requests_export':
+      return await handleRequestsExport(command, browser);
     case 'download':
       return await handleDownload(command, browser);
     case 'geolocation':
@@ -445,6 +480,8 @@ async function dispatchAction(command: Command, browser: BrowserManager): Promis
       return await handleIsChecked(command, browser);
     case 'count':
       return await handleCount(command, browser);
+    case 'assert':
+      return await handleAssert(command, browser);
     case 'boundingbox':
       return await handleBoundingBox(command, browser);
     case 'styles':
@@ -457,6 +494,8 @@ async function dispatchAction(command: Command, browser: BrowserManager): Promis
       return await handleTraceStart(command, browser);
     case 'trace_stop':
       return await handleTraceStop(command, browser);
+    case 'trace_chunk':
+      return await handleTraceChunk(command, browser);
     case 'profiler_start':
       return await handleProfilerStart(command, browser);
     case 'profiler_stop':
@@ -519,6 +558,16 @@ async function dispatchAction(command: Command, browser: BrowserManager): Promis
       return await handleEmulateMedia(command, browser);
     case 'offline':
       return await handleOffline(command, browser);
+    case 'proxy':
+      return await handleProxy(command, browser);
+    case 'network_conditions':
+      return await handleNetworkConditions(command, browser);
+    case 'cpu_throttle':
+      return await handleCpuThrottle(command, browser);
+    case 'timeout':
+      return handleTimeout(command, browser);
+    case 'last_output':
+      return handleLastOutput(command, browser);
     case 'headers':
       return await handleHeaders(command, browser);
     case 'pause':
@@ -596,7 +645,7 @@ async function dispatchAction(command: Command, browser: BrowserManager): Promis
     default: {
       // TypeScript narrows to never here, but we handle it for safety
       const unknownCommand = command as { id: string; action: string };
-      return errorResponse(unknownCommand.id, `Unknown action: ${unknownCommand.action}`);
+      return errorResponse(unknownCommand.id, `Unknown action: ${unknownCommand.action}`, undefined, 'usage');
     }
   }
 }
@@ -916,6 +965,7 @@ async function handleSnapshot(
     maxDepth?: number;
     compact?: boolean;
     selector?: string;
+    hiddenText?: boolean;
   },
   browser: BrowserManager
 ): Promise<Response<SnapshotData>> {
@@ -939,6 +989,7 @@ async function handleSnapshot(
     snapshot: tree || 'Empty page',
     refs: Object.keys(simpleRefs).length > 0 ? simpleRefs : undefined,
     origin: page.url(),
+    hiddenText: command.hiddenText ? await findHiddenText(page, command.selector) : undefined,
   });
 }
 
@@ -960,13 +1011,13 @@ async function handleWait(command: WaitCommand, browser: BrowserManager): Promis
   if (command.selector) {
     await page.waitForSelector(command.selector, {
       state: command.state ?? 'visible',
-      timeout: command.timeout,
+      timeout: command.timeout ?? browser.getWaitTimeout(),
     });
   } else if (command.timeout) {
     await page.waitForTimeout(command.timeout);
   } else {
     // Default: wait for load state
-    await page.waitForLoadState('load');
+    await page.waitForLoadState('load', { timeout: browser.getWaitTimeout() });
   }
 
   return successResponse(command.id, { waited: true });
@@ -1420,6 +1471,47 @@ async function handleRequests(
   return successResponse(command.id, { requests });
 }
 
+async function handleRequestsExport(
+  command: RequestsExportCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const requests = browser.getRequests(command.filter);
+  if (requests.length === 0) {
+    return errorResponse(
+      command.id,
+      command.filter
+        ? `No captured requests match "${command.filter}"`
+        : "No requests captured. Run 'network requests' first to start tracking."
+    );
+  }
+
+  let selected = requests;
+  if (command.index !== undefined) {
+    const request = requests[command.index];
+    if (!request) {
+      return errorResponse(
+        command.id,
+        `Request index ${command.index} out of range (${requests.length} captured)`
+      );
+    }
+    selected = [request];
+  }
+
+  const format = command.format ?? 'curl';
+  const context = browser.getPage().context();
+  const exports = [];
+  for (const request of selected) {
+    const cookies = await context.cookies(request.url);
+    const snippet = exportRequest(request, format, {
+      cookies: cookies.map((c) => `${c.name}=${c.value}`).join('; ') || undefined,
+      redactAuth: command.redactAuth,
+    });
+    exports.push({ method: request.method, url: request.url, snippet });
+  }
+
+  return successResponse(command.id, { format, exports });
+}
+
 async function handleDownload(
   command: DownloadCommand,
   browser: BrowserManager
@@ -1611,6 +1703,116 @@ async function handleCount(command: CountCommand, browser: BrowserManager): Prom
   return successResponse(command.id, { count });
 }
 
+const DEFAULT_ASSERT_TIMEOUT = 5000;
+const ASSERT_POLL_INTERVAL = 100;
+
+/**
+ * Evaluate an assertion, re-checking until it passes or the timeout expires.
+ * Failures carry expected/actual in `data` so the CLI can report them.
+ */
+async function handleAssert(command: AssertCommand, browser: BrowserManager): Promise<Response> {
+  const page = browser.getPage();
+  const subject =
+    command.check === 'visible'
+      ? command.selector
+      : command.selector
+        ? `${command.check} of ${command.selector}`
+        : command.check;
+
+  if (['text', 'count', 'visible'].includes(command.check) && !command.selector) {
+    return errorResponse(command.id, `assert ${command.check} requires a selector`);
+  }
+  if (command.matches !== undefined) {
+    parsePattern(command.matches); // fail fast on invalid patterns
+  }
+
+  const evaluate = async (): Promise<{ passed: boolean; actual: unknown }> => {
+    switch (command.check) {
+      case 'text': {
+        const locator = browser.getLocator(command.selector!);
+        const actual = (await locator.count()) > 0 ? await locator.first().textContent() : null;
+        return { passed: matchText(actual, command), actual };
+      }
+      case 'url': {
+        const actual = page.url();
+        return { passed: matchText(actual, command), actual };
+      }
+      case 'title': {
+        const actual = await page.title();
+        return { passed: matchText(actual, command), actual };
+      }
+      case 'count': {
+        const actual = await browser.getLocator(command.selector!).count();
+        return { passed: matchCount(actual, command), actual };
+      }
+      case 'visible': {
+        const actual = await browser.getLocator(command.selector!).first().isVisible();
+        return { passed: actual, actual };
+      }
+      case 'no-console-errors': {
+        const actual = [
+          ...browser
+            .getConsoleMessages()
+            .filter((m) => m.type === 'error')
+            .map((m) => m.text),
+          ...browser.getPageErrors().map((e) => e.message),
+        ];
+        return { passed: actual.length === 0, actual };
+      }
+    }
+  };
+
+  // Console errors are already collected; there is nothing to wait for
+  const timeout =
+    command.check === 'no-console-errors' ? 0 : (command.timeout ?? DEFAULT_ASSERT_TIMEOUT);
+  const expected =
+    command.check === 'no-console-errors'
+      ? 'no errors'
+      : command.check === 'visible'
+        ? 'visible'
+        : describeExpected(command);
+  // A check can throw while the page navigates (e.g. the execution context
+  // is destroyed); that counts as not passed yet, and the error is reported
+  // if the check still throws when time runs out
+  let lastError: string | undefined;
+  const attempt = async (): Promise<{ passed: boolean; actual: unknown }> => {
+    try {
+      const result = await evaluate();
+      lastError = undefined;
+      return result;
+    } catch (error) {
+      lastError = error instanceof Error ? error.message : String(error);
+      return { passed: false, actual: null };
+    }
+  };
+
+  const deadline = Date.now() + timeout;
+  let result = await attempt();
+  while (!result.passed && Date.now() < deadline) {
+    await new Promise((resolve) => setTimeout(resolve, ASSERT_POLL_INTERVAL));
+    result = await attempt();
+  }
+
+  const data = {
+    assertion: command.check,
+    selector: command.selector,
+    expected,
+    actual: result.actual,
+    timeout,
+  };
+  if (result.passed) {
+    return successResponse(command.id, { passed: true, ...data });
+  }
+  const got =
+    lastError !== undefined ? `an error: ${lastError}` : JSON.stringify(result.actual);
+  return errorResponse(
+    command.id,
+    `Assertion failed: ${subject} expected ${expected}, got ${got}`,
+    { passed: false, ...data },
+    'assertion_failed'
+  );
+}
+
 async function handleBoundingBox(
   command: BoundingBoxCommand,
   browser: BrowserManager
@@ -1707,6 +1909,10 @@ async function handleTraceStart(
   command: TraceStartCommand,
   browser: BrowserManager
 ): Promise<Response> {
+  if (command.report) {
+    const started = await browser.startReportTracing();
+    return successResponse(command.id, { started });
+  }
   await browser.startTracing({
     screenshots: command.screenshots,
     snapshots: command.snapshots,
@@ -1725,6 +1931,14 @@ async function handleTraceStop(
   );
 }
 
+async function handleTraceChunk(
+  command: TraceChunkCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const saved = await browser.saveTraceChunk(command.path);
+  return successResponse(command.id, { path: saved ? command.path : null });
+}
+
 async function handleProfilerStart(
   command: ProfilerStartCommand,
   browser: BrowserManager
@@ -2190,6 +2404,45 @@ async function handleOffline(command: OfflineCommand, browser: BrowserManager):
   return successResponse(command.id, { offline: command.offline });
 }
 
+async function handleProxy(command: ProxyCommand, browser: BrowserManager): Promise<Response> {
+  await browser.switchProxy(command.proxy);
+  return successResponse(command.id, { proxy: command.proxy.server });
+}
+
+async function handleNetworkConditions(
+  command: NetworkConditionsCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const conditions = resolveNetworkConditions(command.profile, command);
+  await browser.setNetworkConditions(conditions);
+  return successResponse(command.id, { profile: command.profile, conditions });
+}
+
+async function handleCpuThrottle(
+  command: CpuThrottleCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  await browser.setCpuThrottlingRate(command.rate);
+  return successResponse(command.id, { rate: command.rate });
+}
+
+function handleLastOutput(command: LastOutputCommand, browser: BrowserManager): Response {
+  const last = browser.getLastOutput();
+  if (!last) {
+    return errorResponse(command.id, 'No output to page through. Run a command such as snapshot first.');
+  }
+  return successResponse(command.id, last.data);
+}
+
+function handleTimeout(command: TimeoutCommand, browser: BrowserManager): Response {
+  browser.setOperationTimeout(command.kind, command.timeout);
+  return successResponse(command.id, {
+    kind: command.kind,
+    timeout: command.timeout,
+    slowest: browser.getSlowestTimeout(),
+  });
+}
+
 async function handleHeaders(command: HeadersCommand, browser: BrowserManager): Promise<Response> {
   await browser.setExtraHeaders(command.headers);
   return successResponse(command.id, { set: true });
@@ -2290,7 +2543,7 @@ async function handleWaitForUrl(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  await page.waitForURL(command.url, { timeout: command.timeout });
+  await page.waitForURL(command.url, { timeout: command.timeout ?? browser.getWaitTimeout() });
   return successResponse(command.id, { url: page.url() });
 }
 
@@ -2299,7 +2552,9 @@ async function handleWaitForLoadState(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  await page.waitForLoadState(command.state, { timeout: command.timeout });
+  await page.waitForLoadState(command.state, {
+    timeout: command.timeout ?? browser.getWaitTimeout(),
+  });
   return successResponse(command.id, { state: command.state });
 }
 
@@ -2384,7 +2639,9 @@ async function handleWaitForFunction(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  await page.waitForFunction(command.expression, { timeout: command.timeout });
+  await page.waitForFunction(command.expression, {
+    timeout: command.timeout ?? browser.getWaitTimeout(),
+  });
   return successResponse(command.id, { waited: true });
 }
 
@@ -2440,7 +2697,9 @@ async function handleWaitForDownload(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  const download = await page.waitForEvent('download', { timeout: command.timeout });
+  const download = await page.waitForEvent('download', {
+    timeout: command.timeout ?? browser.getWaitTimeout(),
+  });
 
   let filePath: string;
   if (command.path) {
@@ -0,0 +1,62 @@
+import { describe, it, expect } from 'vitest';
+import { describeExpected, matchCount, matchText, parsePattern } from './assertions.js';
+
+describe('assertions', () => {
+  describe('parsePattern', () => {
+    it('should accept bare regex sources', () => {
+      expect(parsePattern('^https://.*/dashboard$').test('https://a.test/dashboard')).toBe(true);
+    });
+
+    it('should accept /source/flags literals', () => {
+      expect(parsePattern('/welcome/i').test('WELCOME back')).toBe(true);
+    });
+
+    it('should treat slash-delimited URL paths as bare sources', () => {
+      const pattern = parsePattern('/dashboard/settings');
+      expect(pattern.flags).toBe('');
+      expect(pattern.test('https://a.test/dashboard/settings')).toBe(true);
+      expect(parsePattern('/app/team/members/').test('https://a.test/app/team/members/')).toBe(
+        true
+      );
+      expect(parsePattern('/a/ii').test('/a/ii')).toBe(true);
+    });
+
+    it('should report invalid patterns', () => {
+      expect(() => parsePattern('(')).toThrow("Invalid --matches pattern '('");
+    });
+  });
+
+  describe('matchText', () => {
+    it('should normalize whitespace for equals and contains', () => {
+      expect(matchText('  Hello\n   world ', { equals: 'Hello world' })).toBe(true);
+      expect(matchText('Signed in as\n  alice', { contains: 'as alice' })).toBe(true);
+    });
+
+    it('should match regexes against the raw text', () => {
+      expect(matchText('Order #1234', { matches: '#\\d{4}$' })).toBe(true);
+    });
+
+    it('should fail for missing elements', () => {
+      expect(matchText(null, { contains: 'x' })).toBe(false);
+    });
+
+    it('should require a matcher', () => {
+      expect(() => matchText('x', {})).toThrow('Expected one of');
+    });
+  });
+
+  describe('matchCount', () => {
+    it('should compare counts', () => {
+      expect(matchCount(3, { eq: 3 })).toBe(true);
+      expect(matchCount(3, { gt: 3 })).toBe(false);
+      expect(matchCount(2, { lt: 3 })).toBe(true);
+    });
+  });
+
+  describe('describeExpected', () => {
+    it('should describe the matcher', () => {
+      expect(describeExpected({ contains: 'Welcome' })).toBe('contains "Welcome"');
+      expect(describeExpected({ eq: 3 })).toBe('== 3');
+    });
+  });
+});
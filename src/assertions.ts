@@ -0,0 +1,75 @@
+export type AssertCheck = 'text' | 'url' | 'title' | 'count' | 'visible' | 'no-console-errors';
+
+export interface TextMatcher {
+  contains?: string;
+  equals?: string;
+  matches?: string;
+}
+
+export interface CountMatcher {
+  eq?: number;
+  gt?: number;
+  lt?: number;
+}
+
+/**
+ * Parse a `--matches` pattern. Accepts `/source/flags` or a bare regex source.
+ * A slash-delimited value whose "flags" are not valid flags, such as the URL
+ * path `/dashboard/settings`, is a bare source.
+ */
+export function parsePattern(pattern: string): RegExp {
+  const literal = /^\/(.*)\/([dgimsuvy]*)$/s.exec(pattern);
+  if (literal) {
+    try {
+      return new RegExp(literal[1], literal[2]);
+    } catch {
+      // Repeated or conflicting flags: fall back to the bare source
+    }
+  }
+  try {
+    return new RegExp(pattern);
+  } catch (e: unknown) {
+    const msg = e instanceof Error ? e.message : String(e);
+    throw new Error(`Invalid --matches pattern '${pattern}': ${msg}`);
+  }
+}
+
+/**
+ * Collapse runs of whitespace and trim, like Playwright's toHaveText.
+ */
+export function normalizeText(text: string): string {
+  return text.replace(/\s+/g, ' ').trim();
+}
+
+/**
+ * Human-readable expectation, e.g. `contains "Welcome"` or `== 3`.
+ */
+export function describeExpected(matcher: TextMatcher & CountMatcher): string {
+  if (matcher.equals !== undefined) return `equals ${JSON.stringify(matcher.equals)}`;
+  if (matcher.contains !== undefined) return `contains ${JSON.stringify(matcher.contains)}`;
+  if (matcher.matches !== undefined) return `matches ${matcher.matches}`;
+  if (matcher.eq !== undefined) return `== ${matcher.eq}`;
+  if (matcher.gt !== undefined) return `> ${matcher.gt}`;
+  if (matcher.lt !== undefined) return `< ${matcher.lt}`;
+  return 'true';
+}
+
+/**
+ * Check text against a matcher. Whitespace is normalized for equals/contains;
+ * regexes see the raw text.
+ */
+export function matchText(actual: string | null, matcher: TextMatcher): boolean {
+  if (actual === null) return false;
+  if (matcher.matches !== undefined) return parsePattern(matcher.matches).test(actual);
+  const normalized = normalizeText(actual);
+  if (matcher.equals !== undefined) return normalized === normalizeText(matcher.equals);
+  if (matcher.contains !== undefined) return normalized.includes(normalizeText(matcher.contains));
+  throw new Error('Expected one of --contains, --equals or --matches');
+}
+
+export function matchCount(actual: number, matcher: CountMatcher): boolean {
+  if (matcher.eq !== undefined) return actual === matcher.eq;
+  if (matcher.gt !== undefined) return actual > matcher.gt;
+  if (matcher.lt !== undefined) return actual < matcher.lt;
+  throw new Error('Expected one of --eq, --gt or --lt');
+}
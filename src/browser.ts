@@ -22,6 +22,20 @@ import type { LaunchCommand, TraceEvent } from './types.js';
 import { type RefMap, type EnhancedSnapshot, getEnhancedSnapshot, parseRef } from './snapshot.js';
 import { safeHeaderMerge } from './state-utils.js';
 import { isDomainAllowed, installDomainFilter, parseDomainList } from './domain-filter.js';
+import {
+  installResourceBlocking,
+  kbpsToBytesPerSecond,
+  parseBlockList,
+  type NetworkConditions,
+} from './network-emulation.js';
+import {
+  buildCaCertArgs,
+  buildProxyPacArgs,
+  buildResolveArgs,
+  parseClientCertsEnv,
+  resolveClientCertificates,
+  type PlaywrightClientCertificate,
+} from './network-trust.js';
 import {
   getEncryptionKey,
   isEncryptedPayload,
@@ -47,6 +61,28 @@ export function getDefaultTimeout(): number {
   return 25000;
 }
 
+/**
+ * Reads an optional per-category timeout in milliseconds from the environment.
+ */
+function getTimeoutFromEnv(name: string): number | undefined {
+  const parsed = parseInt(process.env[name] ?? '', 10);
+  return !isNaN(parsed) && parsed > 0 ? parsed : undefined;
+}
+
+export type TimeoutKind = 'default' | 'navigation' | 'action' | 'wait';
+
+/**
+ * Split a comma-separated environment variable into trimmed, non-empty entries.
+ */
+function splitEnvList(value: string | undefined): string[] {
+  return value
+    ? value
+        .split(',')
+        .map((v) => v.trim())
+        .filter(Boolean)
+    : [];
+}
+
 // Screencast frame data from CDP
 export interface ScreencastFrame {
   data: string; // base64 encoded image
@@ -75,6 +111,7 @@ interface TrackedRequest {
   url: string;
   method: string;
   headers: Record<string, string>;
+  postData?: string;
   timestamp: number;
   resourceType: string;
 }
@@ -109,16 +146,35 @@ export class BrowserManager {
   private activeFrame: Frame | null = null;
   private dialogHandler: ((dialog: Dialog) => Promise<void>) | null = null;
   private trackedRequests: TrackedRequest[] = [];
+  private requestTrackingPage: Page | null = null;
+  private extraHeaders: Record<string, string> | null = null;
   private routes: Map<string, (route: Route) => Promise<void>> = new Map();
   private consoleMessages: ConsoleMessage[] = [];
+  private tracing: boolean = false;
+  // The running trace was started for --report failure artifacts, not by the user
+  private reportTracing: boolean = false;
   private pageErrors: PageError[] = [];
   private isRecordingHar: boolean = false;
   private refMap: RefMap = {};
   private lastSnapshot: string = '';
+  private lastOutput: { action: string; data: unknown } | null = null;
   private scopedHeaderRoutes: Map<string, (route: Route) => Promise<void>> = new Map();
   private colorScheme: 'light' | 'dark' | 'no-preference' | null = null;
   private downloadPath: string | null = null;
   private allowedDomains: string[] = [];
+  private blockedResourceTypes: string[] = [];
+  private clientCertificates: PlaywrightClientCertificate[] = [];
+  private timeouts: Record<TimeoutKind, number | undefined> = {
+    default: getDefaultTimeout(),
+    navigation: getTimeoutFromEnv('AGENT_BROWSER_NAVIGATION_TIMEOUT'),
+    action: getTimeoutFromEnv('AGENT_BROWSER_ACTION_TIMEOUT'),
+    wait: getTimeoutFromEnv('AGENT_BROWSER_WAIT_TIMEOUT'),
+  };
+  private ephemeralContextOptions: {
+    extraHTTPHeaders?: Record<string, string>;
+    userAgent?: string;
+    ignoreHTTPSErrors: boolean;
+  } | null = null;
 
   /**
    * Set the persistent color scheme preference.
@@ -130,6 +186,13 @@ export class BrowserManager {
 
   // CDP session for screencast and input injection
   private cdpSession: CDPSession | null = null;
+
+  // Throttling, applied to every page through a CDP session of its own so
+  // it survives tab switches and reaches tabs opened later
+  private offline: boolean = false;
+  private networkConditions: NetworkConditions | null = null;
+  private cpuThrottlingRate: number = 1;
+  private throttleSessions = new WeakMap<Page, CDPSession>();
   private screencastActive: boolean = false;
   private screencastSessionId: number = 0;
   private frameCallback: ((frame: ScreencastFrame) => void) | null = null;
@@ -198,6 +261,21 @@ export class BrowserManager {
     this.lastSnapshot = snapshot;
   }
 
+  /**
+   * Remember the result of the last content command so the CLI can page
+   * through output it truncated without running the command again.
+   */
+  setLastOutput(action: string, data: unknown): void {
+    this.lastOutput = { action, data };
+  }
+
+  /**
+   * Get the result cached by setLastOutput, if any
+   */
+  getLastOutput(): { action: string; data: unknown } | null {
+    return this.lastOutput;
+  }
+
   /**
    * Get the cached ref map from last snapshot
    */
@@ -249,13 +327,26 @@ export class BrowserManager {
   }
 
   /**
-   * Install the domain filter on a context if an allowlist is configured.
+   * Context options for configured mTLS client certificates (empty when none).
+   */
+  private clientCertificateOptions(): {
+    clientCertificates?: PlaywrightClientCertificate[];
+  } {
+    return this.clientCertificates.length > 0
+      ? { clientCertificates: this.clientCertificates }
+      : {};
+  }
+
+  /**
+   * Install the domain filter and resource blocking on a context if configured.
    * Should be called before any pages navigate on the context.
    */
-  private async ensureDomainFilter(context: BrowserContext): Promise<void> {
+  private async ensureRequestFilters(context: BrowserContext): Promise<void> {
     if (this.allowedDomains.length > 0) {
       await installDomainFilter(context, this.allowedDomains);
     }
+    // Registered last so it runs first; unblocked requests fall back to the domain filter
+    await installResourceBlocking(context, this.blockedResourceTypes);
   }
 
   /**
@@ -339,11 +430,12 @@ export class BrowserManager {
     } else if (this.browser) {
       context = await this.browser.newContext({
         ...(this.colorScheme && { colorScheme: this.colorScheme }),
+        ...this.clientCertificateOptions(),
       });
-      context.setDefaultTimeout(getDefaultTimeout());
+      this.applyTimeouts(context);
       this.contexts.push(context);
       this.setupContextTracking(context);
-      await this.ensureDomainFilter(context);
+      await this.ensureRequestFilters(context);
     } else {
       return;
     }
@@ -450,13 +542,14 @@ export class BrowserManager {
   /**
    * Start tracking requests
    */
-  startRequestTracking(): void {
-    const page = this.getPage();
+  startRequestTracking(page: Page = this.getPage()): void {
+    this.requestTrackingPage = page;
     page.on('request', (request: Request) => {
       this.trackedRequests.push({
         url: request.url(),
         method: request.method(),
         headers: request.headers(),
+        postData: request.postData() ?? undefined,
         timestamp: Date.now(),
         resourceType: request.resourceType(),
       });
@@ -691,16 +784,97 @@ export class BrowserManager {
    * Set offline mode
    */
   async setOffline(offline: boolean): Promise<void> {
+    this.offline = offline;
     const context = this.contexts[0];
     if (context) {
       await context.setOffline(offline);
     }
+    // Throttled pages carry their own network conditions, which must not
+    // report the page as online again
+    await Promise.all(this.pages.map((page) => this.applyThrottling(page)));
+  }
+
+  /**
+   * Emulate network conditions on every page, including pages opened later
+   * (Chromium only). Pass null to remove throttling.
+   */
+  async setNetworkConditions(conditions: NetworkConditions | null): Promise<void> {
+    this.networkConditions = conditions;
+    await Promise.all(this.pages.map((page) => this.applyThrottling(page)));
+  }
+
+  /**
+   * Slow down the CPU on every page, including pages opened later, by the
+   * given factor (Chromium only). A rate of 1 removes throttling.
+   */
+  async setCpuThrottlingRate(rate: number): Promise<void> {
+    this.cpuThrottlingRate = rate;
+    await Promise.all(this.pages.map((page) => this.applyThrottling(page)));
+  }
+
+  /**
+   * Apply the current network and CPU throttling to a page. Pages that were
+   * never throttled get no CDP session while throttling is off.
+   */
+  private async applyThrottling(page: Page): Promise<void> {
+    const conditions = this.networkConditions;
+    let cdp = this.throttleSessions.get(page);
+    if (!cdp) {
+      if (!conditions && this.cpuThrottlingRate === 1) return;
+      cdp = await page.context().newCDPSession(page);
+      this.throttleSessions.set(page, cdp);
+      await cdp.send('Network.enable');
+    }
+    await cdp.send('Network.emulateNetworkConditions', {
+      offline: this.offline,
+      latency: conditions?.latencyMs ?? 0,
+      downloadThroughput: conditions ? kbpsToBytesPerSecond(conditions.downloadKbps) : -1,
+      uploadThroughput: conditions ? kbpsToBytesPerSecond(conditions.uploadKbps) : -1,
+    });
+    await cdp.send('Emulation.setCPUThrottlingRate', { rate: this.cpuThrottlingRate });
+  }
+
+  /**
+   * Apply the configured navigation and action timeouts to a context.
+   */
+  private applyTimeouts(context: BrowserContext): void {
+    const fallback = this.timeouts.default ?? getDefaultTimeout();
+    context.setDefaultTimeout(this.timeouts.action ?? fallback);
+    context.setDefaultNavigationTimeout(this.timeouts.navigation ?? fallback);
+  }
+
+  /**
+   * Change one operation timeout. Open contexts are updated immediately;
+   * categories without a timeout of their own follow the default.
+   */
+  setOperationTimeout(kind: TimeoutKind, ms: number): void {
+    this.timeouts[kind] = ms;
+    for (const context of this.contexts) {
+      this.applyTimeouts(context);
+    }
+  }
+
+  /**
+   * Longest timeout any operation can currently take, so the CLI knows how
+   * long to wait for a response.
+   */
+  getSlowestTimeout(): number {
+    const set = Object.values(this.timeouts).filter((ms): ms is number => ms !== undefined);
+    return Math.max(...set);
+  }
+
+  /**
+   * Timeout for wait commands that do not pass their own.
+   */
+  getWaitTimeout(): number {
+    return this.timeouts.wait ?? this.timeouts.default ?? getDefaultTimeout();
   }
 
   /**
    * Set extra HTTP headers (global - all requests)
    */
   async setExtraHeaders(headers: Record<string, string>): Promise<void> {
+    this.extraHeaders = headers;
     const context = this.contexts[0];
     if (context) {
       await context.setExtraHTTPHeaders(headers);
@@ -780,10 +954,16 @@ export class BrowserManager {
   async startTracing(options: { screenshots?: boolean; snapshots?: boolean }): Promise<void> {
     const context = this.contexts[0];
     if (context) {
+      // A user trace replaces the report's own trace
+      if (this.reportTracing) {
+        await context.tracing.stop();
+        this.reportTracing = false;
+      }
       await context.tracing.start({
         screenshots: options.screenshots ?? true,
         snapshots: options.snapshots ?? true,
       });
+      this.tracing = true;
     }
   }
 
@@ -793,10 +973,47 @@ export class BrowserManager {
   async stopTracing(path?: string): Promise<void> {
     const context = this.contexts[0];
     if (context) {
+      if (this.reportTracing) {
+        throw new Error('No trace is being recorded. Run trace start first.');
+      }
       await context.tracing.stop(path ? { path } : undefined);
+      this.tracing = false;
     }
   }
 
+  /**
+   * Start a trace for --report failure artifacts unless one is already
+   * recording. Returns whether the report's own trace is recording, which is
+   * false while a trace started with `trace start` runs.
+   */
+  async startReportTracing(): Promise<boolean> {
+    const context = this.contexts[0];
+    if (!context) {
+      return false;
+    }
+    if (!this.tracing) {
+      await context.tracing.start({ screenshots: true, snapshots: true });
+      this.tracing = true;
+      this.reportTracing = true;
+    }
+    return this.reportTracing;
+  }
+
+  /**
+   * Save the report's trace recorded so far and keep tracing. Returns false
+   * if the report's trace is not recording; a trace started with
+   * `trace start` is never chunked, so `trace stop` still saves all of it.
+   */
+  async saveTraceChunk(path: string): Promise<boolean> {
+    const context = this.contexts[0];
+    if (!context || !this.reportTracing) {
+      return false;
+    }
+    await context.tracing.stopChunk({ path });
+    await context.tracing.startChunk();
+    return true;
+  }
+
   /**
    * Get the current browser context (first context)
    */
@@ -957,7 +1174,7 @@ export class BrowserManager {
       context.setDefaultTimeout(10000);
       this.contexts.push(context);
       this.setupContextTracking(context);
-      await this.ensureDomainFilter(context);
+      await this.ensureRequestFilters(context);
       await this.sanitizeExistingPages([page]);
       this.pages.push(page);
       this.activePageIndex = 0;
@@ -1097,10 +1314,10 @@ export class BrowserManager {
       this.kernelSessionId = session.session_id;
       this.kernelApiKey = kernelApiKey;
       this.browser = browser;
-      context.setDefaultTimeout(getDefaultTimeout());
+      this.applyTimeouts(context);
       this.contexts.push(context);
       this.setupContextTracking(context);
-      await this.ensureDomainFilter(context);
+      await this.ensureRequestFilters(context);
       await this.sanitizeExistingPages([page]);
       this.pages.push(page);
       this.activePageIndex = 0;
@@ -1172,10 +1389,10 @@ export class BrowserManager {
       this.browserUseSessionId = session.id;
       this.browserUseApiKey = browserUseApiKey;
       this.browser = browser;
-      context.setDefaultTimeout(getDefaultTimeout());
+      this.applyTimeouts(context);
       this.contexts.push(context);
       this.setupContextTracking(context);
-      await this.ensureDomainFilter(context);
+      await this.ensureRequestFilters(context);
       await this.sanitizeExistingPages([page]);
       this.pages.push(page);
       this.activePageIndex = 0;
@@ -1251,6 +1468,18 @@ export class BrowserManager {
       }
     }
 
+    this.clientCertificates = resolveClientCertificates(
+      options.clientCerts?.length
+        ? options.clientCerts
+        : parseClientCertsEnv(process.env.AGENT_BROWSER_CLIENT_CERTS)
+    );
+
+    if (options.block && options.block.length > 0) {
+      this.blockedResourceTypes = parseBlockList(options.block);
+    } else if (process.env.AGENT_BROWSER_BLOCK) {
+      this.blockedResourceTypes = parseBlockList(process.env.AGENT_BROWSER_BLOCK.split(','));
+    }
+
     if (this.downloadPath && (cdpEndpoint || options.autoConnect)) {
       const warning =
         "--download-path is ignored when connecting via CDP or auto-connect (downloads use the remote browser's configuration)";
@@ -1319,6 +1548,21 @@ export class BrowserManager {
       throw new Error('allowFileAccess is only supported in Chromium');
     }
 
+    // Custom CA trust and host resolver overrides map to Chromium flags
+    const caCerts = options.caCerts?.length
+      ? options.caCerts
+      : splitEnvList(process.env.AGENT_BROWSER_CA_CERTS);
+    const resolveEntries = options.resolve?.length
+      ? options.resolve
+      : splitEnvList(process.env.AGENT_BROWSER_RESOLVE);
+    const proxyPac = options.proxyPac ?? process.env.AGENT_BROWSER_PROXY_PAC;
+    if (
+      (caCerts.length > 0 || resolveEntries.length > 0 || proxyPac) &&
+      browserType !== 'chromium'
+    ) {
+      throw new Error('caCerts, resolve and proxyPac are only supported in Chromium');
+    }
+
     const launcher =
       browserType === 'firefox' ? firefox : browserType === 'webkit' ? webkit : chromium;
 
@@ -1328,10 +1572,16 @@ export class BrowserManager {
     const fileAccessArgs = options.allowFileAccess
       ? ['--allow-file-access-from-files', '--allow-file-access']
       : [];
+    const implicitArgs = [
+      ...fileAccessArgs,
+      ...buildCaCertArgs(caCerts),
+      ...buildResolveArgs(resolveEntries),
+      ...buildProxyPacArgs(proxyPac),
+    ];
     const baseArgs = options.args
-      ? [...fileAccessArgs, ...options.args]
-      : fileAccessArgs.length > 0
-        ? fileAccessArgs
+      ? [...implicitArgs, ...options.args]
+      : implicitArgs.length > 0
+        ? implicitArgs
         : undefined;
 
     // Auto-detect args that control window size and disable viewport emulation
@@ -1367,6 +1617,7 @@ export class BrowserManager {
           ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
           ...(this.colorScheme && { colorScheme: this.colorScheme }),
           ...(this.downloadPath && { downloadsPath: this.downloadPath }),
+          ...this.clientCertificateOptions(),
         }
       );
       this.isPersistentContext = true;
@@ -1385,6 +1636,7 @@ export class BrowserManager {
         ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
         ...(this.colorScheme && { colorScheme: this.colorScheme }),
         ...(this.downloadPath && { downloadsPath: this.downloadPath }),
+        ...this.clientCertificateOptions(),
       });
       this.isPersistentContext = true;
     } else {
@@ -1464,21 +1716,25 @@ export class BrowserManager {
         }
       }
 
-      context = await this.browser.newContext({
-        viewport,
+      this.ephemeralContextOptions = {
         extraHTTPHeaders: options.headers,
         userAgent: options.userAgent,
+        ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
+      };
+      context = await this.browser.newContext({
+        ...this.ephemeralContextOptions,
+        viewport,
         storageState,
         ...(options.proxy && { proxy: options.proxy }),
-        ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
         ...(this.colorScheme && { colorScheme: this.colorScheme }),
+        ...this.clientCertificateOptions(),
       });
     }
 
-    context.setDefaultTimeout(getDefaultTimeout());
+    this.applyTimeouts(context);
     this.contexts.push(context);
     this.setupContextTracking(context);
-    await this.ensureDomainFilter(context);
+    await this.ensureRequestFilters(context);
 
     const page = context.pages()[0] ?? (await context.newPage());
     await this.sanitizeExistingPages([page]);
@@ -1490,6 +1746,74 @@ export class BrowserManager {
     this.activePageIndex = this.pages.length > 0 ? this.pages.length - 1 : 0;
   }
 
+  /**
+   * Replace the browser context with one routed through a different proxy,
+   * carrying over cookies and localStorage. Used for proxy rotation on `open`.
+   * Only supported for locally launched, non-persistent browsers.
+   */
+  async switchProxy(proxy: {
+    server: string;
+    bypass?: string;
+    username?: string;
+    password?: string;
+  }): Promise<void> {
+    if (!this.browser || !this.ephemeralContextOptions || this.contexts.length === 0) {
+      throw new Error(
+        'Proxy rotation requires a locally launched browser (not --profile, --extension, --cdp or a provider)'
+      );
+    }
+
+    const oldContexts = this.contexts;
+    const storageState = await oldContexts[0].storageState();
+    const viewport = this.getPage().viewportSize();
+    await this.invalidateCDPSession();
+
+    const context = await this.browser.newContext({
+      ...this.ephemeralContextOptions,
+      viewport,
+      storageState,
+      proxy,
+      ...(this.colorScheme && { colorScheme: this.colorScheme }),
+      ...this.clientCertificateOptions(),
+    });
+    this.applyTimeouts(context);
+    if (this.extraHeaders) {
+      await context.setExtraHTTPHeaders(this.extraHeaders);
+    }
+
+    const trackingRequests = this.requestTrackingPage !== null;
+    this.contexts = [context];
+    this.pages = [];
+    this.activePageIndex = 0;
+    this.activeFrame = null;
+    this.tracing = false; // tracing belonged to the closed context
+    this.reportTracing = false;
+    for (const old of oldContexts) {
+      await old.close().catch(() => {});
+    }
+
+    this.setupContextTracking(context);
+    await this.ensureRequestFilters(context);
+    const page = await context.newPage();
+    if (!this.pages.includes(page)) {
+      this.pages.push(page);
+      this.setupPageTracking(page);
+    }
+    this.activePageIndex = this.pages.indexOf(page);
+
+    // Carry page-level routes and request tracking over to the new page.
+    // Other tabs and sessionStorage do not survive the switch.
+    for (const [url, handler] of this.routes) {
+      await page.route(url, handler);
+    }
+    for (const [pattern, handler] of this.scopedHeaderRoutes) {
+      await page.route(pattern, handler);
+    }
+    if (trackingRequests) {
+      this.startRequestTracking(page);
+    }
+  }
+
   /**
    * Connect to a running browser via CDP (Chrome DevTools Protocol)
    * @param cdpEndpoint Either a port number (as string) or a full WebSocket URL (ws:// or wss://)
@@ -1555,7 +1879,7 @@ export class BrowserManager {
         context.setDefaultTimeout(10000);
         this.contexts.push(context);
         this.setupContextTracking(context);
-        await this.ensureDomainFilter(context);
+        await this.ensureRequestFilters(context);
       }
 
       await this.sanitizeExistingPages(allPages);
@@ -1722,6 +2046,7 @@ export class BrowserManager {
     if (this.colorScheme) {
       page.emulateMedia({ colorScheme: this.colorScheme }).catch(() => {});
     }
+    this.applyThrottling(page).catch(() => {});
 
     page.on('console', (msg) => {
       this.consoleMessages.push({
@@ -1811,11 +2136,12 @@ export class BrowserManager {
     const context = await this.browser.newContext({
       viewport: viewport === undefined ? { width: 1280, height: 720 } : viewport,
       ...(this.colorScheme && { colorScheme: this.colorScheme }),
+      ...this.clientCertificateOptions(),
     });
-    context.setDefaultTimeout(getDefaultTimeout());
+    this.applyTimeouts(context);
     this.contexts.push(context);
     this.setupContextTracking(context);
-    await this.ensureDomainFilter(context);
+    await this.ensureRequestFilters(context);
 
     const page = await context.newPage();
     // Only add if not already tracked (setupContextTracking may have already added it via 'page' event)
@@ -2550,10 +2876,19 @@ export class BrowserManager {
     this.kernelSessionId = null;
     this.kernelApiKey = null;
     this.isPersistentContext = false;
+    this.ephemeralContextOptions = null;
+    this.tracing = false;
+    this.reportTracing = false;
     this.activePageIndex = 0;
     this.colorScheme = null;
     this.refMap = {};
     this.lastSnapshot = '';
+    this.lastOutput = null;
+    this.requestTrackingPage = null;
+    this.extraHeaders = null;
+    this.offline = false;
+    this.networkConditions = null;
+    this.cpuThrottlingRate = 1;
     this.frameCallback = null;
   }
 }
@@ -4,7 +4,7 @@ import * as path from 'path';
 import * as os from 'os';
 import { BrowserManager } from './browser.js';
 import { IOSManager } from './ios-manager.js';
-import { parseCommand, serializeResponse, errorResponse } from './protocol.js';
+import { parseCommand, serializeResponse, errorResponse, errorCodeFor } from './protocol.js';
 import { executeCommand, initActionPolicy } from './actions.js';
 import { executeIOSCommand } from './ios-actions.js';
 import { StreamServer } from './stream-server.js';
@@ -382,7 +382,12 @@ export async function startDaemon(options?: {
           const parseResult = parseCommand(line);
 
           if (!parseResult.success) {
-            const resp = errorResponse(parseResult.id ?? 'unknown', parseResult.error);
+            const resp = errorResponse(
+              parseResult.id ?? 'unknown',
+              parseResult.error,
+              undefined,
+              'usage'
+            );
             await safeWrite(socket, serializeResponse(resp) + '\n');
             continue;
           }
@@ -402,7 +407,9 @@ export async function startDaemon(options?: {
               const message = err instanceof Error ? err.message : String(err);
               await safeWrite(
                 socket,
-                serializeResponse(errorResponse(parseResult.command.id, message)) + '\n'
+                serializeResponse(
+                  errorResponse(parseResult.command.id, message, undefined, errorCodeFor(err))
+                ) + '\n'
               );
             }
             continue;
@@ -561,9 +568,10 @@ export async function startDaemon(options?: {
           await safeWrite(socket, serializeResponse(response) + '\n');
         } catch (err) {
           const message = err instanceof Error ? err.message : String(err);
-          await safeWrite(socket, serializeResponse(errorResponse('error', message)) + '\n').catch(
-            () => {}
-          ); // Socket may already be destroyed
+          await safeWrite(
+            socket,
+            serializeResponse(errorResponse('error', message, undefined, errorCodeFor(err))) + '\n'
+          ).catch(() => {}); // Socket may already be destroyed
         }
       }
 
@@ -4,14 +4,19 @@
  */
 
 import type { IOSManager } from './ios-manager.js';
-import type { Command, Response } from './types.js';
+import type { Command, ErrorCode, Response } from './types.js';
+import { classifyError, errorCodeFor } from './protocol.js';
 
 function successResponse<T>(id: string, data: T): Response<T> {
   return { id, success: true, data };
 }
 
-function errorResponse(id: string, error: string): Response {
-  return { id, success: false, error };
+function errorResponse(
+  id: string,
+  error: string,
+  errorCode: ErrorCode | undefined = classifyError(error)
+): Response {
+  return errorCode ? { id, success: false, error, errorCode } : { id, success: false, error };
 }
 
 /**
@@ -268,6 +273,6 @@ export async function executeIOSCommand(command: Command, manager: IOSManager):
     }
   } catch (error) {
     const message = error instanceof Error ? error.message : String(error);
-    return errorResponse(id, message);
+    return errorResponse(id, message, errorCodeFor(error));
   }
 }
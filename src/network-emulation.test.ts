@@ -0,0 +1,66 @@
+import { describe, it, expect } from 'vitest';
+import {
+  kbpsToBytesPerSecond,
+  parseBlockList,
+  resolveNetworkConditions,
+} from './network-emulation.js';
+
+describe('network-emulation', () => {
+  describe('resolveNetworkConditions', () => {
+    it('should resolve presets', () => {
+      expect(resolveNetworkConditions('slow-3g')).toEqual({
+        downloadKbps: 400,
+        uploadKbps: 400,
+        latencyMs: 2000,
+      });
+    });
+
+    it('should return null for off', () => {
+      expect(resolveNetworkConditions('off')).toBeNull();
+    });
+
+    it('should apply custom values', () => {
+      expect(resolveNetworkConditions('custom', { download: 1000, latency: 50 })).toEqual({
+        downloadKbps: 1000,
+        uploadKbps: 0,
+        latencyMs: 50,
+      });
+    });
+
+    it('should let overrides adjust a preset', () => {
+      expect(resolveNetworkConditions('4g', { latency: 20 })?.latencyMs).toBe(20);
+    });
+
+    it('should reject unknown profiles', () => {
+      expect(() => resolveNetworkConditions('5g')).toThrow('Unknown network profile: 5g');
+    });
+  });
+
+  describe('kbpsToBytesPerSecond', () => {
+    it('should convert kilobits to bytes', () => {
+      expect(kbpsToBytesPerSecond(400)).toBe(50000);
+    });
+
+    it('should map zero to unlimited', () => {
+      expect(kbpsToBytesPerSecond(0)).toBe(-1);
+    });
+  });
+
+  describe('parseBlockList', () => {
+    it('should map categories to resource types', () => {
+      expect(parseBlockList(['images', 'fonts', 'media'])).toEqual(['image', 'font', 'media']);
+    });
+
+    it('should accept singular and mixed-case names', () => {
+      expect(parseBlockList(['Image', ' font '])).toEqual(['image', 'font']);
+    });
+
+    it('should ignore empty entries and duplicates', () => {
+      expect(parseBlockList(['images', '', 'images'])).toEqual(['image']);
+    });
+
+    it('should reject unknown categories', () => {
+      expect(() => parseBlockList(['scripts'])).toThrow('Unknown block category: scripts');
+    });
+  });
+});
@@ -0,0 +1,106 @@
+import type { BrowserContext, Route } from 'playwright-core';
+
+export interface NetworkConditions {
+  downloadKbps: number;
+  uploadKbps: number;
+  latencyMs: number;
+}
+
+/**
+ * Throttling presets, matching the values Chrome DevTools uses for its
+ * built-in profiles (throughput already scaled for packet overhead).
+ */
+export const NETWORK_PROFILES: Record<string, NetworkConditions> = {
+  'slow-3g': { downloadKbps: 400, uploadKbps: 400, latencyMs: 2000 },
+  'fast-3g': { downloadKbps: 1440, uploadKbps: 675, latencyMs: 563 },
+  '4g': { downloadKbps: 8100, uploadKbps: 1350, latencyMs: 165 },
+};
+
+/**
+ * Resolve a profile name (plus optional overrides) into concrete conditions.
+ * Returns null for "off", which clears throttling.
+ * Missing custom values default to unthrottled.
+ */
+export function resolveNetworkConditions(
+  profile: string,
+  overrides: { download?: number; upload?: number; latency?: number } = {}
+): NetworkConditions | null {
+  if (profile === 'off') return null;
+
+  let base: NetworkConditions;
+  if (profile === 'custom') {
+    base = { downloadKbps: 0, uploadKbps: 0, latencyMs: 0 };
+  } else {
+    const preset = NETWORK_PROFILES[profile];
+    if (!preset) {
+      throw new Error(
+        `Unknown network profile: ${profile}. Valid profiles: ${Object.keys(NETWORK_PROFILES).join(', ')}, custom, off`
+      );
+    }
+    base = preset;
+  }
+
+  return {
+    downloadKbps: overrides.download ?? base.downloadKbps,
+    uploadKbps: overrides.upload ?? base.uploadKbps,
+    latencyMs: overrides.latency ?? base.latencyMs,
+  };
+}
+
+/**
+ * Convert kbps to the bytes/second unit CDP expects.
+ * CDP uses -1 for "no limit", so an unset (0) throughput maps to that.
+ */
+export function kbpsToBytesPerSecond(kbps: number): number {
+  return kbps > 0 ? Math.round((kbps * 1000) / 8) : -1;
+}
+
+/**
+ * User-facing block categories mapped to Playwright resource types.
+ */
+export const BLOCK_CATEGORIES: Record<string, string[]> = {
+  images: ['image'],
+  fonts: ['font'],
+  media: ['media'],
+  stylesheets: ['stylesheet'],
+};
+
+/**
+ * Parse block categories (e.g. ["images", "fonts"]) into resource types.
+ * Accepts singular forms too. Throws on unknown categories so typos are not silently ignored.
+ */
+export function parseBlockList(categories: string[]): string[] {
+  const types = new Set<string>();
+  for (const raw of categories) {
+    const name = raw.trim().toLowerCase();
+    if (!name) continue;
+    const mapped = BLOCK_CATEGORIES[name] ?? BLOCK_CATEGORIES[`${name}s`];
+    if (!mapped) {
+      throw new Error(
+        `Unknown block category: ${raw}. Valid categories: ${Object.keys(BLOCK_CATEGORIES).join(', ')}`
+      );
+    }
+    mapped.forEach((t) => types.add(t));
+  }
+  return [...types];
+}
+
+/**
+ * Abort requests of the given resource types on a context. Installed after the
+ * domain filter so it runs first; other requests fall through to earlier routes.
+ */
+export async function installResourceBlocking(
+  context: BrowserContext,
+  resourceTypes: string[]
+): Promise<void> {
+  if (resourceTypes.length === 0) return;
+  const blocked = new Set(resourceTypes);
+
+  await context.route('**/*', async (route: Route) => {
+    if (blocked.has(route.request().resourceType())) {
+      await route.abort('blockedbyclient');
+    } else {
+      await route.fallback();
+    }
+  });
+}
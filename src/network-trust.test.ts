@@ -0,0 +1,125 @@
+import { describe, it, expect } from 'vitest';
+import {
+  buildProxyPacArgs,
+  buildResolveArgs,
+  parseClientCertsEnv,
+  resolveClientCertificates,
+  resolveEntryToRule,
+  splitPemBundle,
+} from './network-trust.js';
+
+describe('network-trust', () => {
+  describe('resolveEntryToRule', () => {
+    it('should map host:port to an address', () => {
+      expect(resolveEntryToRule('staging.internal:443:10.0.0.5')).toBe(
+        'MAP staging.internal:443 10.0.0.5'
+      );
+    });
+
+    it('should bracket IPv6 addresses', () => {
+      expect(resolveEntryToRule('api.test:8443:::1')).toBe('MAP api.test:8443 [::1]');
+    });
+
+    it('should keep bracketed IPv6 addresses', () => {
+      expect(resolveEntryToRule('api.test:80:[fd00::2]')).toBe('MAP api.test:80 [fd00::2]');
+    });
+
+    it('should reject entries without a port', () => {
+      expect(() => resolveEntryToRule('example.com:10.0.0.1')).toThrow('expected host:port:address');
+    });
+
+    it('should reject non-numeric ports', () => {
+      expect(() => resolveEntryToRule('example.com:https:10.0.0.1')).toThrow(
+        'expected host:port:address'
+      );
+    });
+  });
+
+  describe('buildResolveArgs', () => {
+    it('should return no args for an empty list', () => {
+      expect(buildResolveArgs([])).toEqual([]);
+    });
+
+    it('should join rules into one flag', () => {
+      expect(buildResolveArgs(['a.test:443:127.0.0.1', 'b.test:80:127.0.0.2'])).toEqual([
+        '--host-resolver-rules=MAP a.test:443 127.0.0.1, MAP b.test:80 127.0.0.2',
+      ]);
+    });
+  });
+
+  describe('buildProxyPacArgs', () => {
+    it('should return no args when unset', () => {
+      expect(buildProxyPacArgs(undefined)).toEqual([]);
+    });
+
+    it('should pass URLs through', () => {
+      expect(buildProxyPacArgs('http://wpad.corp/proxy.pac')).toEqual([
+        '--proxy-pac-url=http://wpad.corp/proxy.pac',
+      ]);
+    });
+
+    it('should fail for a missing file', () => {
+      expect(() => buildProxyPacArgs('/nonexistent/proxy.pac')).toThrow('Cannot read PAC file');
+    });
+  });
+
+  describe('splitPemBundle', () => {
+    it('should split concatenated certificates', () => {
+      const bundle = [
+        '-----BEGIN CERTIFICATE-----\nAAA\n-----END CERTIFICATE-----',
+        '-----BEGIN CERTIFICATE-----\nBBB\n-----END CERTIFICATE-----',
+      ].join('\n');
+      expect(splitPemBundle(bundle)).toHaveLength(2);
+    });
+
+    it('should return nothing for non-PEM input', () => {
+      expect(splitPemBundle('not a certificate')).toEqual([]);
+    });
+  });
+
+  describe('resolveClientCertificates', () => {
+    const entry = {
+      origin: 'https://internal.example.com',
+      cert: '/certs/client.pem',
+      key: '/certs/client.key',
+    };
+
+    it('should map entries to Playwright options', () => {
+      expect(resolveClientCertificates([entry], {})).toEqual([
+        {
+          origin: 'https://internal.example.com',
+          certPath: '/certs/client.pem',
+          keyPath: '/certs/client.key',
+        },
+      ]);
+    });
+
+    it('should read the passphrase from the environment', () => {
+      const [resolved] = resolveClientCertificates([{ ...entry, passphraseEnv: 'CERT_PASS' }], {
+        CERT_PASS: 'hunter2',
+      });
+      expect(resolved.passphrase).toBe('hunter2');
+    });
+
+    it('should fail when the passphrase variable is missing', () => {
+      expect(() =>
+        resolveClientCertificates([{ ...entry, passphraseEnv: 'CERT_PASS' }], {})
+      ).toThrow('CERT_PASS is not set');
+    });
+  });
+
+  describe('parseClientCertsEnv', () => {
+    it('should return an empty list when unset', () => {
+      expect(parseClientCertsEnv(undefined)).toEqual([]);
+    });
+
+    it('should parse a JSON array', () => {
+      const value = JSON.stringify([{ origin: 'https://a.test', cert: 'c.pem', key: 'k.pem' }]);
+      expect(parseClientCertsEnv(value)).toHaveLength(1);
+    });
+
+    it('should reject non-array JSON', () => {
+      expect(() => parseClientCertsEnv('{}')).toThrow('must be a JSON array');
+    });
+  });
+});
@@ -0,0 +1,165 @@
+import { createHash, X509Certificate } from 'node:crypto';
+import { readFileSync } from 'node:fs';
+
+const PEM_CERT_RE = /-----BEGIN CERTIFICATE-----[\s\S]+?-----END CERTIFICATE-----/g;
+
+/**
+ * Split a PEM bundle into individual certificates.
+ */
+export function splitPemBundle(pem: string): string[] {
+  return pem.match(PEM_CERT_RE) ?? [];
+}
+
+/**
+ * Base64-encoded SHA-256 of a certificate's SubjectPublicKeyInfo, the format
+ * Chromium expects in --ignore-certificate-errors-spki-list.
+ */
+export function spkiHash(pem: string): string {
+  const cert = new X509Certificate(pem);
+  const spki = cert.publicKey.export({ type: 'spki', format: 'der' });
+  return createHash('sha256').update(spki).digest('base64');
+}
+
+/**
+ * Build the Chromium flag that trusts the given PEM files. Chromium accepts a
+ * chain when any certificate in it (leaf or CA) matches one of the hashes, so
+ * pass the CA that signs the staging certificates or the leaf itself.
+ */
+export function buildCaCertArgs(paths: string[]): string[] {
+  const hashes: string[] = [];
+  for (const file of paths) {
+    let content: string;
+    try {
+      content = readFileSync(file, 'utf8');
+    } catch (e: unknown) {
+      const msg = e instanceof Error ? e.message : String(e);
+      throw new Error(`Cannot read CA certificate '${file}': ${msg}`);
+    }
+    const certs = splitPemBundle(content);
+    if (certs.length === 0) {
+      throw new Error(`No PEM certificate found in '${file}'`);
+    }
+    for (const cert of certs) {
+      try {
+        hashes.push(spkiHash(cert));
+      } catch (e: unknown) {
+        const msg = e instanceof Error ? e.message : String(e);
+        throw new Error(`Invalid certificate in '${file}': ${msg}`);
+      }
+    }
+  }
+  return hashes.length > 0 ? [`--ignore-certificate-errors-spki-list=${hashes.join(',')}`] : [];
+}
+
+/**
+ * Convert a curl-style `host:port:address` entry into a Chromium host-resolver
+ * rule. The port scopes the mapping; the original port is kept on connect.
+ */
+export function resolveEntryToRule(entry: string): string {
+  const first = entry.indexOf(':');
+  const second = first === -1 ? -1 : entry.indexOf(':', first + 1);
+  if (second === -1) {
+    throw new Error(`Invalid --resolve entry '${entry}': expected host:port:address`);
+  }
+  const host = entry.slice(0, first);
+  const port = entry.slice(first + 1, second);
+  let address = entry.slice(second + 1);
+  if (!host || !/^\d+$/.test(port) || !address) {
+    throw new Error(`Invalid --resolve entry '${entry}': expected host:port:address`);
+  }
+  if (address.includes(':') && !address.startsWith('[')) {
+    address = `[${address}]`;
+  }
+  return `MAP ${host}:${port} ${address}`;
+}
+
+export function buildResolveArgs(entries: string[]): string[] {
+  if (entries.length === 0) return [];
+  return [`--host-resolver-rules=${entries.map(resolveEntryToRule).join(', ')}`];
+}
+
+/**
+ * Build the Chromium flag for a proxy auto-config script. URLs are passed
+ * through; local files are inlined as a data: URL so relative paths and
+ * file access restrictions don't matter.
+ */
+export function buildProxyPacArgs(pac: string | undefined): string[] {
+  if (!pac) return [];
+  if (/^(https?|file|data):/i.test(pac)) {
+    return [`--proxy-pac-url=${pac}`];
+  }
+  let script: string;
+  try {
+    script = readFileSync(pac, 'utf8');
+  } catch (e: unknown) {
+    const msg = e instanceof Error ? e.message : String(e);
+    throw new Error(`Cannot read PAC file '${pac}': ${msg}`);
+  }
+  const encoded = Buffer.from(script, 'utf8').toString('base64');
+  return [`--proxy-pac-url=data:application/x-ns-proxy-autoconfig;base64,${encoded}`];
+}
+
+/**
+ * Client certificate entry as configured by the CLI (`--client-cert` / `clientCerts`).
+ */
+export interface ClientCertEntry {
+  origin: string;
+  cert: string;
+  key: string;
+  passphraseEnv?: string;
+}
+
+export interface PlaywrightClientCertificate {
+  origin: string;
+  certPath: string;
+  keyPath: string;
+  passphrase?: string;
+}
+
+/**
+ * Map configured client certificates to Playwright's `clientCertificates` option.
+ * Passphrases are read from the named environment variable so they never travel
+ * through CLI arguments or the command socket.
+ */
+export function resolveClientCertificates(
+  entries: ClientCertEntry[],
+  env: NodeJS.ProcessEnv = process.env
+): PlaywrightClientCertificate[] {
+  return entries.map((entry) => {
+    if (!entry.origin || !entry.cert || !entry.key) {
+      throw new Error('Client certificate requires origin, cert and key');
+    }
+    const resolved: PlaywrightClientCertificate = {
+      origin: entry.origin,
+      certPath: entry.cert,
+      keyPath: entry.key,
+    };
+    if (entry.passphraseEnv) {
+      const passphrase = env[entry.passphraseEnv];
+      if (passphrase === undefined) {
+        throw new Error(
+          `Client certificate for ${entry.origin}: environment variable ${entry.passphraseEnv} is not set`
+        );
+      }
+      resolved.passphrase = passphrase;
+    }
+    return resolved;
+  });
+}
+
+/**
+ * Parse the AGENT_BROWSER_CLIENT_CERTS environment variable (JSON array).
+ */
+export function parseClientCertsEnv(value: string | undefined): ClientCertEntry[] {
+  if (!value) return [];
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(value);
+  } catch {
+    throw new Error('AGENT_BROWSER_CLIENT_CERTS must be a JSON array');
+  }
+  if (!Array.isArray(parsed)) {
+    throw new Error('AGENT_BROWSER_CLIENT_CERTS must be a JSON array');
+  }
+  return parsed as ClientCertEntry[];
+}
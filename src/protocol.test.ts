@@ -1,5 +1,5 @@
 import { describe, it, expect } from 'vitest';
-import { parseCommand } from './protocol.js';
+import { classifyError, errorCodeFor, errorResponse, parseCommand } from './protocol.js';
 
 // Helper to create command JSON string
 const cmd = (obj: object) => JSON.stringify(obj);
@@ -687,6 +687,27 @@ describe('parseCommand', () => {
     });
   });
 
+  describe('assert', () => {
+    it('should parse text assertions', () => {
+      const result = parseCommand(
+        cmd({ id: '1', action: 'assert', check: 'text', selector: '@e1', contains: 'Hi' })
+      );
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse count assertions with a timeout', () => {
+      const result = parseCommand(
+        cmd({ id: '1', action: 'assert', check: 'count', selector: '.row', eq: 3, timeout: 1000 })
+      );
+      expect(result.success).toBe(true);
+    });
+
+    it('should reject unknown checks', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'assert', check: 'enabled' }));
+      expect(result.success).toBe(false);
+    });
+  });
+
   describe('viewport and settings', () => {
     it('should parse viewport', () => {
       const result = parseCommand(cmd({ id: '1', action: 'viewport', width: 1920, height: 1080 }));
@@ -1429,3 +1450,43 @@ describe('parseCommand', () => {
     });
   });
 });
+
+describe('errorResponse', () => {
+  it('should classify thrown errors by message', () => {
+    expect(classifyError("Action denied by policy: 'eval' is not allowed")).toBe('policy_denied');
+    expect(classifyError('Element "#missing" not found or not visible.')).toBe('not_found');
+    expect(classifyError('Page says: the request timed out')).toBeUndefined();
+    expect(classifyError('net::ERR_NAME_NOT_RESOLVED')).toBeUndefined();
+  });
+
+  it('should classify timeouts by error name', () => {
+    const timeout = new Error('page.waitForSelector: Timeout 5000ms exceeded.');
+    timeout.name = 'TimeoutError';
+    expect(errorCodeFor(timeout)).toBe('timeout');
+    expect(errorCodeFor(new Error('Timeout 5000ms exceeded.'))).toBeUndefined();
+    expect(errorCodeFor(new Error('Element "#missing" not found or not visible.'))).toBe(
+      'not_found'
+    );
+  });
+
+  it('should set errorCode only when known', () => {
+    expect(errorResponse('1', 'Timeout 100ms exceeded.', undefined, 'timeout')).toEqual({
+      id: '1',
+      success: false,
+      error: 'Timeout 100ms exceeded.',
+      errorCode: 'timeout',
+    });
+    expect(errorResponse('1', 'Unknown', { passed: false }, 'assertion_failed')).toEqual({
+      id: '1',
+      success: false,
+      error: 'Unknown',
+      data: { passed: false },
+      errorCode: 'assertion_failed',
+    });
+    expect(errorResponse('1', 'Something broke')).toEqual({
+      id: '1',
+      success: false,
+      error: 'Something broke',
+    });
+  });
+});
@@ -1,5 +1,5 @@
 import { z } from 'zod';
-import type { Command, Response } from './types.js';
+import type { Command, ErrorCode, ErrorResponse, Response } from './types.js';
 
 // Base schema for all commands
 const baseCommandSchema = z.object({
@@ -54,6 +54,20 @@ const launchSchema = baseCommandSchema.extend({
   profile: z.string().optional(),
   storageState: z.string().optional(),
   allowedDomains: z.array(z.string()).optional(),
+  block: z.array(z.string()).optional(),
+  caCerts: z.array(z.string()).optional(),
+  resolve: z.array(z.string()).optional(),
+  proxyPac: z.string().optional(),
+  clientCerts: z
+    .array(
+      z.object({
+        origin: z.string().min(1),
+        cert: z.string().min(1),
+        key: z.string().min(1),
+        passphraseEnv: z.string().optional(),
+      })
+    )
+    .optional(),
   actionPolicy: z.string().optional(),
   confirmActions: z.array(z.string()).optional(),
 });
@@ -246,6 +260,14 @@ const requestsSchema = baseCommandSchema.extend({
   clear: z.boolean().optional(),
 });
 
+const requestsExportSchema = baseCommandSchema.extend({
+  action: z.literal('requests_export'),
+  index: z.number().int().nonnegative().optional(),
+  filter: z.string().optional(),
+  format: z.enum(['curl', 'fetch', 'httpie', 'python-requests']).optional(),
+  redactAuth: z.boolean().optional(),
+});
+
 const downloadSchema = baseCommandSchema.extend({
   action: z.literal('download'),
   selector: z.string().min(1),
@@ -332,6 +354,19 @@ const countSchema = baseCommandSchema.extend({
   selector: z.string().min(1),
 });
 
+const assertSchema = baseCommandSchema.extend({
+  action: z.literal('assert'),
+  check: z.enum(['text', 'url', 'title', 'count', 'visible', 'no-console-errors']),
+  selector: z.string().min(1).optional(),
+  contains: z.string().optional(),
+  equals: z.string().optional(),
+  matches: z.string().optional(),
+  eq: z.number().int().nonnegative().optional(),
+  gt: z.number().int().optional(),
+  lt: z.number().int().optional(),
+  timeout: z.number().nonnegative().optional(),
+});
+
 const boundingBoxSchema = baseCommandSchema.extend({
   action: z.literal('boundingbox'),
   selector: z.string().min(1),
@@ -372,6 +407,7 @@ const traceStartSchema = baseCommandSchema.extend({
   action: z.literal('trace_start'),
   screenshots: z.boolean().optional(),
   snapshots: z.boolean().optional(),
+  report: z.boolean().optional(),
 });
 
 const traceStopSchema = baseCommandSchema.extend({
@@ -379,6 +415,11 @@ const traceStopSchema = baseCommandSchema.extend({
   path: z.string().min(1).optional(),
 });
 
+const traceChunkSchema = baseCommandSchema.extend({
+  action: z.literal('trace_chunk'),
+  path: z.string().min(1),
+});
+
 const profilerStartSchema = baseCommandSchema.extend({
   action: z.literal('profiler_start'),
   categories: z.array(z.string()).optional(),
@@ -548,6 +589,39 @@ const offlineSchema = baseCommandSchema.extend({
   offline: z.boolean(),
 });
 
+const proxySchema = baseCommandSchema.extend({
+  action: z.literal('proxy'),
+  proxy: z.object({
+    server: z.string().min(1),
+    bypass: z.string().optional(),
+    username: z.string().optional(),
+    password: z.string().optional(),
+  }),
+});
+
+const networkConditionsSchema = baseCommandSchema.extend({
+  action: z.literal('network_conditions'),
+  profile: z.enum(['slow-3g', 'fast-3g', '4g', 'custom', 'off']),
+  download: z.number().nonnegative().optional(),
+  upload: z.number().nonnegative().optional(),
+  latency: z.number().nonnegative().optional(),
+});
+
+const cpuThrottleSchema = baseCommandSchema.extend({
+  action: z.literal('cpu_throttle'),
+  rate: z.number().min(1),
+});
+
+const lastOutputSchema = baseCommandSchema.extend({
+  action: z.literal('last_output'),
+});
+
+const timeoutSchema = baseCommandSchema.extend({
+  action: z.literal('timeout'),
+  kind: z.enum(['default', 'navigation', 'action', 'wait']),
+  timeout: z.number().int().positive(),
+});
+
 const headersSchema = baseCommandSchema.extend({
   action: z.literal('headers'),
   headers: z.record(z.string()),
@@ -801,6 +875,7 @@ const snapshotSchema = baseCommandSchema.extend({
   maxDepth: z.number().nonnegative().optional(),
   compact: z.boolean().optional(),
   selector: z.string().optional(),
+  hiddenText: z.boolean().optional(),
 });
 
 const evaluateSchema = baseCommandSchema.extend({
@@ -968,6 +1043,7 @@ const commandSchema = z.discriminatedUnion('action', [
   routeSchema,
   unrouteSchema,
   requestsSchema,
+  requestsExportSchema,
   downloadSchema,
   geolocationSchema,
   permissionsSchema,
@@ -985,6 +1061,7 @@ const commandSchema = z.discriminatedUnion('action', [
   isEnabledSchema,
   isCheckedSchema,
   countSchema,
+  assertSchema,
   boundingBoxSchema,
   stylesSchema,
   videoStartSchema,
@@ -994,6 +1071,7 @@ const commandSchema = z.discriminatedUnion('action', [
   recordingRestartSchema,
   traceStartSchema,
   traceStopSchema,
+  traceChunkSchema,
   profilerStartSchema,
   profilerStopSchema,
   harStartSchema,
@@ -1025,6 +1103,11 @@ const commandSchema = z.discriminatedUnion('action', [
   addStyleSchema,
   emulateMediaSchema,
   offlineSchema,
+  networkConditionsSchema,
+  cpuThrottleSchema,
+  timeoutSchema,
+  lastOutputSchema,
+  proxySchema,
   headersSchema,
   pauseSchema,
   getByAltTextSchema,
@@ -1142,8 +1225,37 @@ export function successResponse<T>(id: string, data: T): Response<T> {
 /**
  * Create an error response
  */
-export function errorResponse(id: string, error: string): Response {
-  return { id, success: false, error };
+export function errorResponse(
+  id: string,
+  error: string,
+  data?: unknown,
+  errorCode: ErrorCode | undefined = classifyError(error)
+): Response {
+  const response: ErrorResponse = { id, success: false, error };
+  if (data !== undefined) response.data = data;
+  if (errorCode) response.errorCode = errorCode;
+  return response;
+}
+
+/**
+ * Failure class of an error message, for errors thrown from Playwright or
+ * browser code that carry no explicit code
+ */
+export function classifyError(message: string): ErrorCode | undefined {
+  if (message.startsWith('Action denied by policy')) return 'policy_denied';
+  if (/^Element ".*" not found|^Frame not found|matched 0 elements/.test(message)) {
+    return 'not_found';
+  }
+  return undefined;
+}
+
+/**
+ * Failure class of a caught error. Timeouts are recognised by Playwright's
+ * TimeoutError name, since the message can quote page text.
+ */
+export function errorCodeFor(error: unknown): ErrorCode | undefined {
+  if (error instanceof Error && error.name === 'TimeoutError') return 'timeout';
+  return classifyError(error instanceof Error ? error.message : String(error));
 }
 
 /**
@@ -0,0 +1,111 @@
+import { describe, it, expect } from 'vitest';
+import { exportRequest, prepareHeaders, shellQuote } from './request-export.js';
+
+const getRequest = {
+  url: 'https://api.example.com/items?page=2',
+  method: 'GET',
+  headers: {
+    Accept: 'application/json',
+    Authorization: 'Bearer secret-token',
+    ':authority': 'api.example.com',
+  },
+};
+
+const postRequest = {
+  url: 'https://api.example.com/items',
+  method: 'POST',
+  headers: {
+    'content-type': 'application/json',
+    'content-length': '17',
+  },
+  postData: `{"name":"it's"}`,
+};
+
+describe('request-export', () => {
+  describe('shellQuote', () => {
+    it('should leave safe strings unquoted', () => {
+      expect(shellQuote('https://example.com/a')).toBe('https://example.com/a');
+    });
+
+    it('should escape single quotes', () => {
+      expect(shellQuote("it's")).toBe(`'it'\\''s'`);
+    });
+
+    it('should quote empty strings', () => {
+      expect(shellQuote('')).toBe("''");
+    });
+  });
+
+  describe('prepareHeaders', () => {
+    it('should drop pseudo-headers and content-length', () => {
+      const names = prepareHeaders(postRequest).map(([name]) => name);
+      expect(names).toEqual(['content-type']);
+      expect(prepareHeaders(getRequest).map(([name]) => name)).not.toContain(':authority');
+    });
+
+    it('should lowercase header names', () => {
+      expect(prepareHeaders(getRequest)[0]).toEqual(['accept', 'application/json']);
+    });
+
+    it('should append cookies when the request has no cookie header', () => {
+      const headers = prepareHeaders(getRequest, { cookies: 'sid=abc' });
+      expect(headers).toContainEqual(['cookie', 'sid=abc']);
+    });
+
+    it('should not override an existing cookie header', () => {
+      const req = { ...getRequest, headers: { cookie: 'a=1' } };
+      expect(prepareHeaders(req, { cookies: 'b=2' })).toEqual([['cookie', 'a=1']]);
+    });
+
+    it('should redact auth headers and cookies', () => {
+      const headers = prepareHeaders(getRequest, { cookies: 'sid=abc', redactAuth: true });
+      expect(headers).toContainEqual(['authorization', '<redacted>']);
+      expect(headers).toContainEqual(['cookie', '<redacted>']);
+      expect(headers).toContainEqual(['accept', 'application/json']);
+    });
+  });
+
+  describe('exportRequest', () => {
+    it('should export a GET request as curl', () => {
+      const out = exportRequest(getRequest, 'curl');
+      expect(out).toContain("curl 'https://api.example.com/items?page=2'");
+      expect(out).toContain("-H 'authorization: Bearer secret-token'");
+      expect(out).not.toContain('-X');
+    });
+
+    it('should export a POST request as curl with body', () => {
+      const out = exportRequest(postRequest, 'curl');
+      expect(out).toContain('-X POST');
+      expect(out).toContain(`--data-raw '{"name":"it'\\''s"}'`);
+      expect(out).not.toContain('content-length');
+    });
+
+    it('should export as fetch', () => {
+      const out = exportRequest(postRequest, 'fetch');
+      expect(out.startsWith('await fetch("https://api.example.com/items", {')).toBe(true);
+      expect(out).toContain('"method": "POST"');
+      expect(out).toContain('"body": "{\\"name\\":\\"it\'s\\"}"');
+    });
+
+    it('should export as httpie', () => {
+      const out = exportRequest(getRequest, 'httpie', { redactAuth: true });
+      expect(out).toContain("http GET 'https://api.example.com/items?page=2'");
+      expect(out).toContain("'authorization:<redacted>'");
+    });
+
+    it('should pipe the body into httpie', () => {
+      const out = exportRequest(postRequest, 'httpie');
+      expect(out.startsWith("printf '%s' ")).toBe(true);
+      expect(out).toContain('http POST https://api.example.com/items');
+    });
+
+    it('should export as python-requests', () => {
+      const out = exportRequest(postRequest, 'python-requests');
+      expect(out).toContain('import requests');
+      expect(out).toContain('"content-type": "application/json",');
+      expect(out).toContain(
+        'response = requests.request("POST", "https://api.example.com/items", headers=headers, data=data)'
+      );
+    });
+  });
+});
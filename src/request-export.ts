@@ -0,0 +1,161 @@
+export type RequestExportFormat = 'curl' | 'fetch' | 'httpie' | 'python-requests';
+
+export const REQUEST_EXPORT_FORMATS: RequestExportFormat[] = [
+  'curl',
+  'fetch',
+  'httpie',
+  'python-requests',
+];
+
+export interface ExportableRequest {
+  url: string;
+  method: string;
+  headers: Record<string, string>;
+  postData?: string;
+}
+
+export interface RequestExportOptions {
+  /** Cookie header value to attach when the request headers don't already carry one. */
+  cookies?: string;
+  /** Replace credential-bearing headers with a placeholder. */
+  redactAuth?: boolean;
+}
+
+const REDACTED = '<redacted>';
+
+/**
+ * Headers that carry credentials. Replaced with a placeholder when redaction is requested.
+ */
+const AUTH_HEADERS = new Set([
+  'authorization',
+  'proxy-authorization',
+  'cookie',
+  'x-api-key',
+  'x-auth-token',
+  'x-csrf-token',
+  'x-xsrf-token',
+]);
+
+/**
+ * Headers that the HTTP client computes itself. Copying them verbatim breaks
+ * replays when the body changes (content-length) or is not valid on HTTP/1.1
+ * (HTTP/2 pseudo-headers such as :authority).
+ */
+function isSkippedHeader(name: string): boolean {
+  return name.startsWith(':') || name === 'content-length';
+}
+
+/**
+ * Normalize the headers of a captured request for export: lowercase names,
+ * drop client-managed headers, merge cookies, and optionally redact credentials.
+ * Exported for testing.
+ */
+export function prepareHeaders(
+  req: ExportableRequest,
+  options: RequestExportOptions = {}
+): [string, string][] {
+  const result: [string, string][] = [];
+  let hasCookie = false;
+  for (const [rawName, value] of Object.entries(req.headers)) {
+    const name = rawName.toLowerCase();
+    if (isSkippedHeader(name)) continue;
+    if (name === 'cookie') hasCookie = true;
+    result.push([name, value]);
+  }
+  if (!hasCookie && options.cookies) {
+    result.push(['cookie', options.cookies]);
+  }
+  if (options.redactAuth) {
+    return result.map(([name, value]) => [name, AUTH_HEADERS.has(name) ? REDACTED : value]);
+  }
+  return result;
+}
+
+/**
+ * Quote a string for POSIX shells using single quotes.
+ */
+export function shellQuote(value: string): string {
+  if (value === '') return "''";
+  if (/^[A-Za-z0-9_\-.,:/@%+=]+$/.test(value)) return value;
+  return `'${value.replace(/'/g, `'\\''`)}'`;
+}
+
+function pythonString(value: string): string {
+  return JSON.stringify(value);
+}
+
+function toCurl(req: ExportableRequest, headers: [string, string][]): string {
+  const lines = [`curl ${shellQuote(req.url)}`];
+  // --data-raw implies POST, so the method is spelled out whenever it isn't a plain GET
+  if (req.method !== 'GET' || req.postData !== undefined) {
+    lines.push(`-X ${req.method}`);
+  }
+  for (const [name, value] of headers) {
+    lines.push(`-H ${shellQuote(`${name}: ${value}`)}`);
+  }
+  if (req.postData !== undefined) {
+    lines.push(`--data-raw ${shellQuote(req.postData)}`);
+  }
+  return lines.join(' \\\n  ');
+}
+
+function toFetch(req: ExportableRequest, headers: [string, string][]): string {
+  const init: Record<string, unknown> = { method: req.method };
+  if (headers.length > 0) {
+    init.headers = Object.fromEntries(headers);
+  }
+  if (req.postData !== undefined) {
+    init.body = req.postData;
+  }
+  return `await fetch(${JSON.stringify(req.url)}, ${JSON.stringify(init, null, 2)});`;
+}
+
+function toHttpie(req: ExportableRequest, headers: [string, string][]): string {
+  const lines: string[] = [];
+  if (req.postData !== undefined) {
+    lines.push(`printf '%s' ${shellQuote(req.postData)} |`);
+  }
+  const parts = [`http ${req.method} ${shellQuote(req.url)}`];
+  for (const [name, value] of headers) {
+    parts.push(shellQuote(`${name}:${value}`));
+  }
+  lines.push(parts.join(' \\\n  '));
+  return lines.join(' ');
+}
+
+function toPythonRequests(req: ExportableRequest, headers: [string, string][]): string {
+  const lines = ['import requests', ''];
+  lines.push('headers = {');
+  for (const [name, value] of headers) {
+    lines.push(`    ${pythonString(name)}: ${pythonString(value)},`);
+  }
+  lines.push('}');
+  const args = [pythonString(req.method), pythonString(req.url), 'headers=headers'];
+  if (req.postData !== undefined) {
+    lines.push('', `data = ${pythonString(req.postData)}`);
+    args.push('data=data');
+  }
+  lines.push('', `response = requests.request(${args.join(', ')})`);
+  return lines.join('\n');
+}
+
+/**
+ * Render a captured request as a ready-to-run snippet in the given format.
+ */
+export function exportRequest(
+  req: ExportableRequest,
+  format: RequestExportFormat,
+  options: RequestExportOptions = {}
+): string {
+  const headers = prepareHeaders(req, options);
+  switch (format) {
+    case 'curl':
+      return toCurl(req, headers);
+    case 'fetch':
+      return toFetch(req, headers);
+    case 'httpie':
+      return toHttpie(req, headers);
+    case 'python-requests':
+      return toPythonRequests(req, headers);
+  }
+}
@@ -263,6 +263,64 @@ async function findCursorInteractiveElements(
   return page.evaluate(fn, rootSelector);
 }
 
+/**
+ * Find text that stays in the accessibility tree, and so in the snapshot,
+ * while the page hides it from view: transparent, zero-size, clipped or
+ * pushed off-screen, or the same colour as its background. Used by the CLI's
+ * --injection-scan, since a snapshot carries no styles. Elements with
+ * display: none or visibility: hidden are left out of the snapshot already.
+ */
+export async function findHiddenText(page: Page, selector?: string): Promise<string[]> {
+  const rootSelector = selector || 'body';
+
+  // Use a string function body to avoid TypeScript transpilation issues
+  const scriptBody = `(rootSel) => {
+    const root = document.querySelector(rootSel) || document.body;
+    const skipTags = new Set(['SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE']);
+    const FAR = 999;
+
+    const hidesText = (el) => {
+      const style = getComputedStyle(el);
+      if (parseFloat(style.opacity) === 0) return true;
+      if (parseFloat(style.fontSize) === 0) return true;
+      if (style.color === 'rgba(0, 0, 0, 0)') return true;
+      if (style.color === style.backgroundColor) return true;
+      if (style.clip.startsWith('rect(0') || style.clipPath === 'inset(100%)') return true;
+      if (parseFloat(style.textIndent) <= -FAR) return true;
+      const rect = el.getBoundingClientRect();
+      if (rect.right + window.scrollX < -FAR || rect.bottom + window.scrollY < -FAR) return true;
+      if (rect.width <= 1 && rect.height <= 1 && style.overflow === 'hidden') return true;
+      return false;
+    };
+
+    // Opacity, clipping and position hide descendants without showing up
+    // in their computed style, so check ancestors too
+    const hidden = new Map();
+    const isHidden = (el) => {
+      if (!el || el === document.documentElement) return false;
+      if (hidden.has(el)) return hidden.get(el);
+      const result = hidesText(el) || isHidden(el.parentElement);
+      hidden.set(el, result);
+      return result;
+    };
+
+    const results = new Set();
+    const walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT);
+    while (walker.nextNode() && results.size < 100) {
+      const node = walker.currentNode;
+      const parent = node.parentElement;
+      if (!parent || skipTags.has(parent.tagName)) continue;
+      const text = (node.textContent || '').replace(/\\s+/g, ' ').trim();
+      if (text && isHidden(parent)) results.add(text.slice(0, 500));
+    }
+    return Array.from(results);
+  }`;
+
+  // eslint-disable-next-line @typescript-eslint/no-implied-eval
+  const fn = new Function('return ' + scriptBody)();
+  return page.evaluate(fn, rootSelector);
+}
+
 /**
  * Get enhanced snapshot with refs and optional filtering
  */
@@ -34,6 +34,16 @@ export interface LaunchCommand extends BaseCommand {
   colorScheme?: 'light' | 'dark' | 'no-preference'; // Persistent color scheme override
   downloadPath?: string; // Directory for browser downloads (Playwright's downloadsPath)
   allowedDomains?: string[];
+  block?: string[]; // Resource categories to abort (images, fonts, media, stylesheets)
+  caCerts?: string[]; // PEM files to trust (Chromium only)
+  resolve?: string[]; // host:port:address overrides (Chromium only)
+  proxyPac?: string; // PAC script URL or file path (Chromium only)
+  clientCerts?: Array<{
+    origin: string;
+    cert: string; // PEM certificate path
+    key: string; // PEM private key path
+    passphraseEnv?: string; // Env var holding the key passphrase
+  }>;
   actionPolicy?: string;
   confirmActions?: string[];
   // Auto-load state file for session persistence
@@ -235,6 +245,15 @@ export interface RequestsCommand extends BaseCommand {
   clear?: boolean;
 }
 
+// Replay captured requests outside the browser
+export interface RequestsExportCommand extends BaseCommand {
+  action: 'requests_export';
+  index?: number; // Position in the (filtered) request list
+  filter?: string; // URL pattern to filter
+  format?: 'curl' | 'fetch' | 'httpie' | 'python-requests';
+  redactAuth?: boolean;
+}
+
 // Download handling
 export interface DownloadCommand extends BaseCommand {
   action: 'download';
@@ -330,6 +349,20 @@ export interface CountCommand extends BaseCommand {
   selector: string;
 }
 
+// Assert a page condition, retrying until it holds or the timeout expires
+export interface AssertCommand extends BaseCommand {
+  action: 'assert';
+  check: 'text' | 'url' | 'title' | 'count' | 'visible' | 'no-console-errors';
+  selector?: string;
+  contains?: string;
+  equals?: string;
+  matches?: string;
+  eq?: number;
+  gt?: number;
+  lt?: number;
+  timeout?: number;
+}
+
 // Bounding box
 export interface BoundingBoxCommand extends BaseCommand {
   action: 'boundingbox';
@@ -577,6 +610,8 @@ export interface TraceStartCommand extends BaseCommand {
   action: 'trace_start';
   screenshots?: boolean;
   snapshots?: boolean;
+  // Trace for --report failure artifacts; a no-op while `trace start` records
+  report?: boolean;
 }
 
 export interface TraceStopCommand extends BaseCommand {
@@ -584,6 +619,12 @@ export interface TraceStopCommand extends BaseCommand {
   path?: string;
 }
 
+// Save the trace recorded so far without stopping tracing
+export interface TraceChunkCommand extends BaseCommand {
+  action: 'trace_chunk';
+  path: string;
+}
+
 /**
  * Chrome Trace Event format. All fields are optional because CDP trace event
  * shapes vary across categories and event phases -- this type is intentionally
@@ -791,6 +832,44 @@ export interface OfflineCommand extends BaseCommand {
   offline: boolean;
 }
 
+// Switch the context to another proxy, keeping cookies and storage
+export interface ProxyCommand extends BaseCommand {
+  action: 'proxy';
+  proxy: {
+    server: string;
+    bypass?: string;
+    username?: string;
+    password?: string;
+  };
+}
+
+// Throttle network (Chromium only)
+export interface NetworkConditionsCommand extends BaseCommand {
+  action: 'network_conditions';
+  profile: 'slow-3g' | 'fast-3g' | '4g' | 'custom' | 'off';
+  download?: number; // kbps
+  upload?: number; // kbps
+  latency?: number; // ms
+}
+
+// Throttle CPU (Chromium only)
+export interface CpuThrottleCommand extends BaseCommand {
+  action: 'cpu_throttle';
+  rate: number;
+}
+
+// Return the cached result of the last content command
+export interface LastOutputCommand extends BaseCommand {
+  action: 'last_output';
+}
+
+// Change an operation timeout on the running browser
+export interface TimeoutCommand extends BaseCommand {
+  action: 'timeout';
+  kind: 'default' | 'navigation' | 'action' | 'wait';
+  timeout: number; // ms
+}
+
 // Set extra HTTP headers
 export interface HeadersCommand extends BaseCommand {
   action: 'headers';
@@ -934,6 +1013,7 @@ export type Command =
   | RouteCommand
   | UnrouteCommand
   | RequestsCommand
+  | RequestsExportCommand
   | DownloadCommand
   | GeolocationCommand
   | PermissionsCommand
@@ -951,6 +1031,7 @@ export type Command =
   | IsEnabledCommand
   | IsCheckedCommand
   | CountCommand
+  | AssertCommand
   | BoundingBoxCommand
   | StylesCommand
   | VideoStartCommand
@@ -960,6 +1041,7 @@ export type Command =
   | RecordingRestartCommand
   | TraceStartCommand
   | TraceStopCommand
+  | TraceChunkCommand
   | ProfilerStartCommand
   | ProfilerStopCommand
   | HarStartCommand
@@ -991,6 +1073,11 @@ export type Command =
   | AddStyleCommand
   | EmulateMediaCommand
   | OfflineCommand
+  | NetworkConditionsCommand
+  | ProxyCommand
+  | CpuThrottleCommand
+  | TimeoutCommand
+  | LastOutputCommand
   | HeadersCommand
   | PauseCommand
   | GetByAltTextCommand
@@ -1111,10 +1198,18 @@ export interface SuccessResponse<T = unknown> {
   data: T;
 }
 
+/**
+ * Failure class of an error response. The CLI maps each to its own exit
+ * code, so scripts can branch without parsing messages.
+ */
+export type ErrorCode = 'usage' | 'timeout' | 'not_found' | 'policy_denied' | 'assertion_failed';
+
 export interface ErrorResponse {
   id: string;
   success: false;
   error: string;
+  data?: unknown;
+  errorCode?: ErrorCode;
 }
 
 export type Response<T = unknown> = SuccessResponse<T> | ErrorResponse;
@@ -1143,6 +1238,8 @@ export interface SnapshotData {
   snapshot: string;
   refs?: Record<string, { role: string; name?: string }>;
   origin?: string;
+  // Text in the snapshot that the page hides from view, for --injection-scan
+  hiddenText?: string[];
 }
 
 export interface EvaluateData {